@@ -1,10 +1,18 @@
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 
-use snip3_fixed_multisig::msg::{HandleMsg, InitMsg, QueryMsg};
+use snip3_fixed_multisig::hooks::HookHandleMsg;
+use snip3_fixed_multisig::msg::{HandleMsg, InitMsg, QueryMsg, VoteBatchResponse};
+use snip3_fixed_multisig::query::{
+    ConfigResponse, ContractInfoResponse, DelegationResponse, DepositResponse, HooksResponse, ProposalListResponse,
+    ProposalResponse, SimulateExecuteResponse, VoteListResponse, VoteResponse, VoterListResponse, VoterResponse, VotingPowerResponse,
+};
+use snip3_fixed_multisig::signed::{SignDoc, VoteData};
+use snip3_fixed_multisig::snapshot::StateSnapshot;
 use snip3_fixed_multisig::state::Config;
+use snip3_fixed_multisig::threshold::ThresholdResponse;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -16,4 +24,25 @@ fn main() {
     export_schema(&schema_for!(HandleMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(HookHandleMsg), &out_dir);
+    export_schema(&schema_for!(VoteBatchResponse), &out_dir);
+    export_schema(&schema_for!(SignDoc), &out_dir);
+    export_schema(&schema_for!(VoteData), &out_dir);
+
+    // query responses
+    export_schema(&schema_for!(ThresholdResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(ContractInfoResponse), &out_dir);
+    export_schema_with_title(&mut schema_for!(ProposalResponse), &out_dir, "ProposalResponse");
+    export_schema(&schema_for!(ProposalListResponse), &out_dir);
+    export_schema(&schema_for!(DepositResponse), &out_dir);
+    export_schema(&schema_for!(VoteResponse), &out_dir);
+    export_schema(&schema_for!(VoteListResponse), &out_dir);
+    export_schema(&schema_for!(VoterResponse), &out_dir);
+    export_schema(&schema_for!(VoterListResponse), &out_dir);
+    export_schema(&schema_for!(DelegationResponse), &out_dir);
+    export_schema(&schema_for!(VotingPowerResponse), &out_dir);
+    export_schema(&schema_for!(HooksResponse), &out_dir);
+    export_schema(&schema_for!(SimulateExecuteResponse), &out_dir);
+    export_schema(&schema_for!(StateSnapshot), &out_dir);
 }
@@ -0,0 +1,79 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use cosmwasm_std::Binary;
+
+use crate::expiration::Expiration;
+use crate::query::{ProposalResponse, Status};
+use crate::state::{Proposal, Votes};
+use crate::threshold::ThresholdResponse;
+
+/// What is kept of a pruned proposal: its outcome, without messages or ballots
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ArchivedProposal {
+    pub title_hash: Binary,
+    pub proposer: String,
+    pub start_height: u64,
+    pub start_time: u64,
+    pub expires: Expiration,
+    /// final status, either executed or rejected
+    pub status: Status,
+    pub threshold: ThresholdResponse,
+    pub votes: Votes,
+}
+
+/// Part of `ProposalResponse` only set for pruned proposals
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ArchivedSummary {
+    /// sha256 of the title
+    pub title_hash: Binary,
+    /// final tally
+    pub votes: Votes,
+}
+
+impl ArchivedProposal {
+    pub fn new(prop: &Proposal) -> Self {
+        ArchivedProposal {
+            title_hash: Binary(Sha256::digest(prop.title.as_bytes()).to_vec()),
+            proposer: prop.proposer.clone(),
+            start_height: prop.start_height,
+            start_time: prop.start_time,
+            expires: prop.expires,
+            status: prop.status,
+            threshold: prop.threshold_response(),
+            votes: prop.votes.clone(),
+        }
+    }
+
+    /// the summary form of `ProposalResponse`, with empty title, description and msgs
+    pub fn into_response(self, id: u64) -> ProposalResponse {
+        ProposalResponse {
+            id,
+            title: String::new(),
+            description: String::new(),
+            msgs: vec![],
+            status: self.status,
+            expires: self.expires,
+            threshold: self.threshold,
+            proposer: self.proposer,
+            deposit: None,
+            start_height: self.start_height,
+            start_time: self.start_time,
+            execute_at: None,
+            depends_on: vec![],
+            dependents: vec![],
+            steps: vec![],
+            archived: Some(ArchivedSummary {
+                title_hash: self.title_hash,
+                votes: self.votes,
+            }),
+        }
+    }
+}
+
+/// Only proposals that reached a final status are pruned. Their deposit was settled when
+/// they got there.
+pub fn can_prune(prop: &Proposal) -> bool {
+    matches!(prop.status, Status::Executed | Status::Rejected)
+}
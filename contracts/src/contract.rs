@@ -1,54 +1,165 @@
 use std::cmp::Ordering;
 
 use cosmwasm_std::{
-    log, to_binary, Api, Binary, Env, Extern, HandleResponse, InitResponse, Querier,
-    StdError, StdResult, Storage, CosmosMsg, Empty };
+    from_binary, log, to_binary, Api, Binary, BlockInfo, Env, Extern, HandleResponse, HumanAddr, InitResponse,
+    Querier, StdError, StdResult, Storage, CosmosMsg, Empty, WasmMsg };
+use secret_toolkit::snip20::register_receive_msg;
 
+use crate::archive::{ can_prune, ArchivedProposal };
+use crate::dependency::{ check_dependencies_executed, pending_dependents, validate_dependencies };
+use crate::deposit::{ Deposit, DepositToken, BLOCK_SIZE };
 use crate::error::ContractError;
+use crate::hooks::{ hook_msgs, status_change_msgs, Hook, ProposalHookMsg };
+use crate::house::{ validate_houses, HouseThresholdResponse };
 use crate::expiration::Expiration;
-use crate::msg::{ HandleMsg, InitMsg, QueryMsg, Vote };
-use crate::query::{ ProposalListResponse, ProposalResponse, VoteInfo, VoteListResponse,
-                    VoteResponse, VoterListResponse, VoterResponse, Status };
+use crate::math::Uint128;
+use crate::msg::{ FactoryCallbackMsg, HandleMsg, InitMsg, QueryMsg, ReceiveMsg, SignedVote, Vote, VoteBatchMode,
+                  VoteBatchResponse, VoteBatchResult, Voter };
+use crate::nested::ProposalAction;
+use crate::pause::{ check_not_paused, Pause };
+use crate::rules::{ select_threshold, validate_rules };
+use crate::signed::verify_signed_vote;
+use crate::simulate::{ execution_msgs, simulate };
+use crate::snapshot::{ BallotSnapshot, ImportStatus, ProposalSnapshot, StateSnapshot, SNAPSHOT_VERSION };
+use crate::query::{ ConfigResponse, ContractInfoResponse, DelegatedWeight, DelegationResponse,
+                    DepositResponse, HooksResponse, ProposalListResponse, ProposalResponse, VoteInfo,
+                    VoteListResponse, VoteResponse, VoterDetail, VoterListResponse, VoterResponse,
+                    VotingPowerResponse, SimulateExecuteResponse, Status, StepStatus };
 use crate::state::{ config, config_read, voters, voters_read, proposal_count, proposal_count_read,
-                    ballots, ballots_read, proposals, proposals_read, voters_list, voters_list_read };
-use crate::state::{ Ballot, Config, Proposal, Votes };
+                    ballots, ballots_read, proposals, proposals_read, voters_list, voters_list_read,
+                    open_proposals, add_open_proposal, remove_open_proposal,
+                    deposits, deposits_read, delegations_read, delegators_read, delegated_votes,
+                    delegated_votes_read, set_delegation, remove_delegation, voter_groups,
+                    voter_groups_read, hooks, hooks_read, pause, pause_read, overruled_guardians,
+                    overruled_guardians_read, import_status, import_status_read, schema_version,
+                    archived_proposals, archived_proposals_read, prune_start, prune_start_read,
+                    contract_address, contract_address_read, due_queue_read, add_due_proposal,
+                    remove_due_proposal, dependents, dependents_read, add_dependent, vote_nonces,
+                    vote_nonces_read };
+use crate::state::{ Ballot, Config, DelegatedVote, Delegation, Proposal, Votes };
 use crate::threshold::ThresholdResponse;
+use crate::upgrade::{ member_key, open_proposal_ids, SCHEMA_VERSION };
+use crate::validation::{ validate_proposal, MAX_BATCH_VOTES };
+
+// version info, exposed through the ContractInfo query
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
-    _env: Env,
+    env: Env,
     msg: InitMsg,
 ) -> Result<InitResponse, StdError> {
     if msg.voters.is_empty() {
         return Err(StdError::generic_err((ContractError::NoVoters {}).to_string()));
     }
 
-    let total_weight = msg.voters.iter().map(|v| v.weight).sum();
+    // addresses are stored in their normalized form, and voters keyed by canonical address,
+    // so a duplicate would be counted twice in the total weight but stored once
+    let mut members: Vec<Voter> = Vec::with_capacity(msg.voters.len());
+    for voter in msg.voters {
+        let addr = normalize_address(&deps.api, &voter.addr)?;
+        if members.iter().any(|m| m.addr == addr) {
+            return Err(StdError::generic_err(ContractError::DuplicateVoter {}.to_string()));
+        }
+        members.push(Voter { addr, ..voter });
+    }
+    let proposers = msg.proposers.map(|list| normalize_addresses(&deps.api, list)).transpose()?;
+
+    let total_weight = members
+        .iter()
+        .try_fold(0u64, |total, v| total.checked_add(v.weight))
+        .ok_or_else(|| StdError::generic_err(ContractError::WeightOverflow {}.to_string()))?;
 
     msg.threshold.validate(total_weight)?;
-    // TODO Implement address validation
+
+    // proposers are a subset of the members
+    if let Some(proposers) = &proposers {
+        if proposers.iter().any(|p| !members.iter().any(|v| &v.addr == p)) {
+            return Err(StdError::generic_err(ContractError::UnknownProposer {}.to_string()));
+        }
+    }
+
+    let threshold_rules = msg.threshold_rules.unwrap_or_default();
+    validate_rules(&threshold_rules, total_weight)?;
+
+    let houses = msg.houses.unwrap_or_default();
+    validate_houses(&houses, &members)?;
+
+    let guardians = normalize_addresses(&deps.api, msg.guardians.unwrap_or_default())?;
+
+    // SNIP-20 deposits are paid through Send, so we need to register with the token
+    let mut messages = vec![];
+    if let Some(deposit) = &msg.proposal_deposit {
+        deposit.validate()?;
+        if let DepositToken::Snip20 { address, code_hash } = &deposit.token {
+            messages.push(register_receive_msg(
+                env.contract_code_hash,
+                None,
+                BLOCK_SIZE,
+                code_hash.clone(),
+                address.clone(),
+            )?);
+        }
+    }
 
     let cfg = Config {
         threshold: msg.threshold,
         total_weight,
         max_voting_period: msg.max_voting_period,
+        proposers,
+        proposal_deposit: msg.proposal_deposit,
+        threshold_rules,
+        houses,
+        guardians,
     };
 
     // save the configuration settings
     config(&mut deps.storage).save(&cfg)?;
+    schema_version(&mut deps.storage).save(&SCHEMA_VERSION)?;
+    contract_address(&mut deps.storage).save(&env.contract.address)?;
 
     // save the list of Voters
-    voters_list(&mut deps.storage).save(&msg.voters)?;
+    voters_list(&mut deps.storage).save(&members)?;
     
     // save each voter's address and weight in a key-value pair
-    for voter in msg.voters.iter() {
-        voters(&mut deps.storage).save(voter.addr.as_bytes(), &voter.weight)?;
+    for voter in members.iter() {
+        let key = deps.api.canonical_address(&HumanAddr::from(voter.addr.as_str()))?;
+        voters(&mut deps.storage).save(key.as_slice(), &voter.weight)?;
+        if let Some(group) = &voter.group {
+            voter_groups(&mut deps.storage).save(voter.addr.as_bytes(), group)?;
+        }
     }
 
     // set initial value for proposal count
     proposal_count(&mut deps.storage).save(&0)?;
 
-    Ok(InitResponse::default())
+    // no proposal is open yet
+    open_proposals(&mut deps.storage).save(&vec![])?;
+
+    // and no contract is notified of them
+    hooks(&mut deps.storage).save(&vec![])?;
+
+    let status = ImportStatus {
+        importing: msg.import_state.unwrap_or(false),
+        last_imported_id: 0,
+        approvals: vec![],
+    };
+    import_status(&mut deps.storage).save(&status)?;
+
+    if let Some(callback) = msg.callback {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: callback.contract_addr,
+            callback_code_hash: callback.code_hash,
+            msg: to_binary(&FactoryCallbackMsg::RegisterMultisig { id: callback.id })?,
+            send: vec![],
+        }));
+    }
+
+    Ok(InitResponse {
+        messages,
+        log: vec![],
+    })
 }
 
 pub fn handle<S: Storage, A: Api, Q: Querier>(
@@ -56,35 +167,211 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
     env: Env,
     msg: HandleMsg,
 ) -> Result<HandleResponse<Empty>, StdError> {
+    // contracts deployed before the address was recorded learn it from their next message
+    if contract_address_read(&deps.storage).may_load()?.is_none() {
+        contract_address(&mut deps.storage).save(&env.contract.address)?;
+    }
+
     match msg {
         HandleMsg::Propose {
             title,
             description,
             msgs,
             latest,
-        } => execute_propose(deps, env, title, description, msgs, latest),
+            execute_at,
+            depends_on,
+        } => {
+            let proposal = NewProposal {
+                title,
+                description,
+                msgs,
+                signal: false,
+                stepwise: false,
+                latest,
+                execute_at,
+                depends_on,
+            };
+            execute_propose_native(deps, env, proposal)
+        }
+        HandleMsg::Pause { until } => execute_pause(deps, env, until),
+        HandleMsg::Unpause {} => execute_unpause(deps, env),
+        HandleMsg::UpdateGuardians { guardians } => execute_update_guardians(deps, env, guardians),
+        HandleMsg::ImportState { snapshot } => execute_import_state(deps, env, *snapshot),
+        HandleMsg::FinishImport {} => execute_finish_import(deps, env),
+        HandleMsg::Prune { before_id } => execute_prune(deps, env, before_id),
+        HandleMsg::ProposeAction {
+            title,
+            description,
+            action,
+            latest,
+            execute_at,
+        } => {
+            let proposal = NewProposal::from_action(title, description, action, latest, execute_at)?;
+            execute_propose_native(deps, env, proposal)
+        }
         HandleMsg::Vote { proposal_id, vote } => execute_vote(deps, env, proposal_id, vote),
-        HandleMsg::Execute { proposal_id } => execute_execute(deps, env, proposal_id),
+        HandleMsg::VoteBatch { votes, mode } => execute_vote_batch(deps, env, votes, mode),
+        HandleMsg::SubmitSignedVotes { votes } => execute_submit_signed_votes(deps, env, votes),
+        HandleMsg::Execute { proposal_id, step } => execute_execute(deps, env, proposal_id, step),
         HandleMsg::Close { proposal_id } => execute_close(deps, env, proposal_id),
+        HandleMsg::ExecuteDue { limit, start_after } => execute_execute_due(deps, env, limit, start_after),
+        HandleMsg::Delegate { to, until } => execute_delegate(deps, env, to, until),
+        HandleMsg::Undelegate {} => execute_undelegate(deps, env),
+        HandleMsg::AddHook { address, code_hash } => execute_add_hook(deps, env, address, code_hash),
+        HandleMsg::RemoveHook { address } => execute_remove_hook(deps, env, address),
+        HandleMsg::Receive {
+            sender,
+            from,
+            amount,
+            msg,
+        } => execute_receive(deps, env, sender, from, amount, msg),
+    }
+}
+
+pub fn execute_receive<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    sender: HumanAddr,
+    from: HumanAddr,
+    amount: Uint128,
+    msg: Option<Binary>,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // the calling token must be the configured deposit token
+    let cfg = config_read(&deps.storage).load()?;
+    let deposit = match cfg.proposal_deposit {
+        Some(deposit) => deposit,
+        None => return Err(StdError::generic_err(ContractError::InvalidDeposit {}.to_string())),
+    };
+    deposit.check_snip20_funds(&env.message.sender, amount)?;
+
+    let msg: ReceiveMsg = from_binary(
+        &msg.ok_or_else(|| StdError::generic_err("Receive requires a msg"))?
+    )?;
+    match msg {
+        ReceiveMsg::Propose {
+            title,
+            description,
+            msgs,
+            latest,
+            execute_at,
+            depends_on,
+        } => {
+            // whoever sent the tokens proposes, with their own weight. With `SendFrom` that is
+            // the spender, and the tokens' owner only pays the deposit and gets it back.
+            let deposit = Some(deposit.paid_by(from));
+            let proposal = NewProposal {
+                title,
+                description,
+                msgs,
+                signal: false,
+                stepwise: false,
+                latest,
+                execute_at,
+                depends_on,
+            };
+            execute_propose(deps, env, sender, deposit, proposal)
+        }
+        ReceiveMsg::ProposeAction {
+            title,
+            description,
+            action,
+            latest,
+            execute_at,
+        } => {
+            let deposit = Some(deposit.paid_by(from));
+            let proposal = NewProposal::from_action(title, description, action, latest, execute_at)?;
+            execute_propose(deps, env, sender, deposit, proposal)
+        }
+    }
+}
+
+/// A proposal to create, as given by the propose messages
+pub struct NewProposal {
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+    /// only signalling proposals may have no messages
+    pub signal: bool,
+    /// each message is then executed on its own
+    pub stepwise: bool,
+    /// we ignore earliest
+    pub latest: Option<Expiration>,
+    pub execute_at: Option<Expiration>,
+    /// proposals that must be executed first
+    pub depends_on: Vec<u64>,
+}
+
+impl NewProposal {
+    /// the proposal made by a `ProposeAction` message
+    pub fn from_action(
+        title: String,
+        description: String,
+        action: ProposalAction,
+        latest: Option<Expiration>,
+        execute_at: Option<Expiration>,
+    ) -> StdResult<Self> {
+        Ok(NewProposal {
+            title,
+            description,
+            signal: matches!(action, ProposalAction::Signal {}),
+            stepwise: matches!(action, ProposalAction::Steps { .. }),
+            msgs: action.into_cosmos_msgs()?,
+            latest,
+            execute_at,
+            depends_on: vec![],
+        })
     }
 }
 
+/// Proposes from a handle message, where a native deposit, if any, must be sent along
+pub fn execute_propose_native<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    proposal: NewProposal,
+) -> Result<HandleResponse<Empty>, StdError> {
+    let cfg = config_read(&deps.storage).load()?;
+    let proposer = env.message.sender.clone();
+    let deposit = match cfg.proposal_deposit {
+        Some(deposit) => {
+            deposit.check_native_funds(&env.message.sent_funds)?;
+            Some(deposit.paid_by(proposer.clone()))
+        }
+        None => None,
+    };
+    execute_propose(deps, env, proposer, deposit, proposal)
+}
+
 pub fn execute_propose<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
-    title: String,
-    description: String,
-    msgs: Vec<CosmosMsg>,
-    // we ignore earliest
-    latest: Option<Expiration>,
+    proposer: HumanAddr,
+    // deposit already paid by the proposer, if one is required
+    deposit: Option<Deposit>,
+    proposal: NewProposal,
 ) -> Result<HandleResponse<Empty>, StdError> {
+    let NewProposal { title, description, msgs, signal, stepwise, latest, execute_at, depends_on } = proposal;
+
     // only members of the multisig can create a proposal
-    let vote_power: u64 = voters_read(&deps.storage)
-        .may_load(&env.message.sender.to_string().as_bytes())?
-        .ok_or(StdError::generic_err(ContractError::Unauthorized {}.to_string()))?;
+    let proposer_key = member_key(&deps.storage, &deps.api, &proposer)?;
+    let own_power: u64 = voters_read(&deps.storage)
+        .may_load(proposer_key.as_slice())?
+        .ok_or_else(|| StdError::generic_err(ContractError::Unauthorized {}.to_string()))?;
 
     let cfg = config_read(&deps.storage).load()?;
 
+    validate_proposal(&deps.api, &env.contract.address, &title, &description, &msgs, signal)?;
+    check_not_paused(&deps.storage, &env.block, &msgs, &env.contract.address, ContractError::ProposePaused {})?;
+    if import_status_read(&deps.storage).may_load()?.is_some_and(|status| status.importing) {
+        return Err(StdError::generic_err(ContractError::ImportInProgress {}.to_string()));
+    }
+
+    // when configured, only the listed members can create a proposal
+    if let Some(proposers) = &cfg.proposers {
+        if !proposers.contains(&proposer.to_string()) {
+            return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        }
+    }
+
     // max expires also used as default
     let max_expires = cfg.max_voting_period.after(&env.block);
     let mut expires = latest.unwrap_or(max_expires);
@@ -94,39 +381,101 @@ pub fn execute_propose<S: Storage, A: Api, Q: Querier>(
     } else if comp.is_none() {
         return Err(StdError::generic_err(ContractError::WrongExpiration {}.to_string()));
     }
+    if execute_at == Some(Expiration::Never {}) {
+        return Err(StdError::generic_err(ContractError::InvalidExecuteAt {}.to_string()));
+    }
+
+    // the kinds of messages proposed decide how many votes are needed
+    let threshold = select_threshold(
+        &cfg.threshold_rules,
+        &cfg.threshold,
+        &msgs,
+        &env.contract.address,
+        cfg.total_weight,
+    )?;
+
+    let next_id = proposal_count_read(&deps.storage).load()? + 1;
+    let depends_on = validate_dependencies(&deps.storage, next_id, depends_on)?;
+
+    let proposal_id = proposal_count(&mut deps.storage).update(|mut id| {
+        id += 1;
+        Ok(id)
+    })?;
 
     // create a proposal
+    let voters = voters_list_read(&deps.storage).load()?;
     let mut prop = Proposal {
         title,
         description,
+        proposer: proposer.to_string(),
         start_height: env.block.height,
+        start_time: env.block.time,
         expires,
+        steps: if stepwise { vec![StepStatus::Pending; msgs.len()] } else { vec![] },
         msgs,
         status: Status::Open,
-        votes: Votes::yes(vote_power),
-        threshold: cfg.threshold,
+        votes: Votes::yes(0),
+        threshold,
         total_weight: cfg.total_weight,
+        houses: cfg.houses.iter().map(|house| house.tally(&voters)).collect(),
+        execute_at,
+        depends_on,
     };
-    prop.update_status(&env.block);
-    let proposal_id = proposal_count(&mut deps.storage).update(|mut id| {
-        id += 1;
-        Ok(id)
-    })?;
+
+    // the proposer's yes vote also carries the weight delegated to them
+    let proposer = proposer.to_string();
+    let group = voter_groups_read(&deps.storage).may_load(proposer.as_bytes())?;
+    prop.add_vote(group.as_deref(), Vote::Yes, own_power)?;
+    let (delegated_weight, delegated) =
+        cast_delegated_weight(&deps.storage, &deps.api, &env.block, proposal_id, &mut prop, &proposer, Vote::Yes)?;
+    save_delegated_votes(&mut deps.storage, proposal_id, &delegated)?;
+    let vote_power = add_weight(own_power, delegated_weight)?;
+    prop.update_status(&env.block)?;
     proposals(&mut deps.storage).save(&proposal_id.to_le_bytes(), &prop)?;
 
+    // track it in the open proposals index, unless the proposer's vote already passed it
+    if prop.status == Status::Open {
+        add_open_proposal(&mut deps.storage, proposal_id)?;
+    }
+
+    for dependency in prop.depends_on.iter() {
+        add_dependent(&mut deps.storage, *dependency, proposal_id)?;
+    }
+
+    // queue it for `ExecuteDue`, which skips it until it passed
+    if let Some(execute_at) = execute_at {
+        add_due_proposal(&mut deps.storage, proposal_id, execute_at)?;
+    }
+
+    // hold the deposit until the proposal is executed or rejected
+    if let Some(deposit) = deposit {
+        deposits(&mut deps.storage).save(&proposal_id.to_le_bytes(), &deposit)?;
+    }
+
     // add the first yes vote from voter
     let ballot = Ballot {
         weight: vote_power,
         vote: Vote::Yes,
     };
-    ballots(&mut deps.storage, proposal_id).save(&env.message.sender.to_string().as_bytes(),&ballot)?;
+    ballots(&mut deps.storage, proposal_id).save(proposer_key.as_slice(),&ballot)?;
+
+    let messages = hook_msgs(
+        &deps.storage,
+        &env.contract.address,
+        &prop,
+        ProposalHookMsg::NewProposal {
+            proposal_id,
+            proposer: proposer.clone(),
+            status: prop.status,
+        },
+    )?;
 
     Ok(HandleResponse {
-        messages: vec![],
+        messages,
         log: vec![
             log("action","propose"),
-            log("sender", env.message.sender),
-            log("proposal_id",&proposal_id),
+            log("sender", proposer),
+            log("proposal_id", proposal_id),
             log("status", format!("{:?}", prop.status))],
         data: None
     })
@@ -138,12 +487,12 @@ pub fn execute_vote<S: Storage, A: Api, Q: Querier>(
     proposal_id: u64,
     vote: Vote,
 ) -> Result<HandleResponse<Empty>, StdError> {
-    // only members of the multisig with weight >= 1 can vote
-    let voter_power = voters_read(&deps.storage).may_load(&env.message.sender.to_string().as_bytes())?;
-    let vote_power = match voter_power {
-        Some(power) if power >= 1 => power,
-        _ => return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string())),
-    };
+    // only members of the multisig can vote
+    let voter = env.message.sender.to_string();
+    let voter_key = member_key(&deps.storage, &deps.api, &env.message.sender)?;
+    let own_power = voters_read(&deps.storage)
+        .may_load(voter_key.as_slice())?
+        .ok_or_else(|| StdError::generic_err(ContractError::Unauthorized {}.to_string()))?;
 
     // ensure proposal exists and can be voted on
     let mut prop = proposals_read(&deps.storage).load(&proposal_id.to_le_bytes())?;
@@ -153,26 +502,78 @@ pub fn execute_vote<S: Storage, A: Api, Q: Querier>(
     if prop.expires.is_expired(&env.block) {
         return Err(StdError::generic_err(ContractError::Expired {}.to_string()));
     }
+    check_not_paused(&deps.storage, &env.block, &prop.msgs, &env.contract.address, ContractError::VotePaused {})?;
+    check_not_imported(&deps.storage, proposal_id)?;
+
+    // a voter can only vote once, and only with weight >= 1
+    if ballots_read(&deps.storage, proposal_id).may_load(voter_key.as_slice())?.is_some() {
+        return match own_power {
+            0 => Err(StdError::generic_err(ContractError::Unauthorized {}.to_string())),
+            _ => Err(StdError::generic_err(ContractError::AlreadyVoted {}.to_string())),
+        };
+    }
 
-    // a voter can only vote once
-    if let Some(_ballot) = ballots_read(&deps.storage, proposal_id).may_load(&env.message.sender.to_string().as_bytes())? {
-        return Err(StdError::generic_err(ContractError::AlreadyVoted {}.to_string()))
+    // A failed vote must not write anything, so that `VoteBatch` can skip it: the tally is
+    // worked out on `prop` and the ballots to change, which are only saved once nothing
+    // can fail anymore.
+    let (delegated_weight, delegated) =
+        cast_delegated_weight(&deps.storage, &deps.api, &env.block, proposal_id, &mut prop, &voter, vote)?;
+    let vote_power = add_weight(own_power, delegated_weight)?;
+    if vote_power == 0 {
+        return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
     }
 
+    let group = voter_groups_read(&deps.storage).may_load(voter.as_bytes())?;
+
+    // voting directly overrides a delegate that already cast the voter's weight
+    let overridden = match delegated_votes_read(&deps.storage, proposal_id).may_load(voter.as_bytes())? {
+        Some(delegated) => {
+            let delegate_key = member_key(&deps.storage, &deps.api, &HumanAddr::from(delegated.delegate))?;
+            let mut delegate_ballot = ballots_read(&deps.storage, proposal_id).load(delegate_key.as_slice())?;
+            delegate_ballot.weight = delegate_ballot
+                .weight
+                .checked_sub(delegated.weight)
+                .ok_or_else(|| StdError::generic_err(ContractError::TallyUnderflow {}.to_string()))?;
+            prop.remove_vote(group.as_deref(), delegate_ballot.vote, delegated.weight)?;
+            Some((delegate_key, delegate_ballot))
+        }
+        None => None,
+    };
+
+    // update vote tally, delegated weight was already added
+    prop.add_vote(group.as_deref(), vote, own_power)?;
+    let old_status = prop.status;
+    prop.update_status(&env.block)?;
+
+    if let Some((delegate_key, delegate_ballot)) = overridden {
+        delegated_votes(&mut deps.storage, proposal_id).remove(voter.as_bytes());
+        ballots(&mut deps.storage, proposal_id).save(delegate_key.as_slice(), &delegate_ballot)?;
+    }
+    save_delegated_votes(&mut deps.storage, proposal_id, &delegated)?;
     let ballot = Ballot {
         weight: vote_power,
         vote,
     };
-
-    ballots(&mut deps.storage, proposal_id).save(&env.message.sender.to_string().as_bytes(),&ballot)?;
-
-    // update vote tally
-    prop.votes.add_vote(vote, vote_power);
-    prop.update_status(&env.block);
+    ballots(&mut deps.storage, proposal_id).save(voter_key.as_slice(),&ballot)?;
     proposals(&mut deps.storage).save(&proposal_id.to_le_bytes(), &prop)?;
 
+    // the vote may have passed or rejected the proposal
+    if prop.status != Status::Open {
+        remove_open_proposal(&mut deps.storage, proposal_id)?;
+    }
+    let mut messages = if prop.status == Status::Rejected {
+        remove_due_proposal(&mut deps.storage, proposal_id)?;
+        settle_deposit(&mut deps.storage, &env.contract.address, proposal_id, &prop)?
+    } else {
+        vec![]
+    };
+    messages.extend(status_change_msgs(&deps.storage, &env.contract.address, proposal_id, &prop, old_status)?);
+    if prop.status == Status::Rejected {
+        messages.extend(reject_dependents(&mut deps.storage, &env.contract.address, proposal_id)?);
+    }
+
     Ok(HandleResponse {
-        messages: vec![],
+        messages,
         log: vec![
             log("action","vote"),
             log("sender", env.message.sender),
@@ -182,60 +583,240 @@ pub fn execute_vote<S: Storage, A: Api, Q: Querier>(
     })
 }
 
+pub fn execute_vote_batch<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    votes: Vec<(u64, Vote)>,
+    mode: VoteBatchMode,
+) -> Result<HandleResponse<Empty>, StdError> {
+    if votes.len() > MAX_BATCH_VOTES {
+        return Err(StdError::generic_err(ContractError::TooManyVotes { max: MAX_BATCH_VOTES }.to_string()));
+    }
+
+    let mut messages = vec![];
+    let mut results = vec![];
+    for (proposal_id, vote) in votes {
+        match execute_vote(deps, env.clone(), proposal_id, vote) {
+            Ok(res) => {
+                messages.extend(res.messages);
+                let status = proposals_read(&deps.storage).load(&proposal_id.to_le_bytes())?.status;
+                results.push(VoteBatchResult { proposal_id, status: Some(status), error: None });
+            }
+            Err(err) if mode == VoteBatchMode::BestEffort => {
+                results.push(VoteBatchResult { proposal_id, status: None, error: Some(err.to_string()) });
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    let failed = results.iter().filter(|result| result.error.is_some()).count();
+    Ok(HandleResponse {
+        messages,
+        log: vec![
+            log("action", "vote_batch"),
+            log("sender", env.message.sender),
+            log("voted", results.len() - failed),
+            log("failed", failed)],
+        data: Some(to_binary(&VoteBatchResponse { results })?),
+    })
+}
+
+pub fn execute_submit_signed_votes<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    votes: Vec<SignedVote>,
+) -> Result<HandleResponse<Empty>, StdError> {
+    if votes.len() > MAX_BATCH_VOTES {
+        return Err(StdError::generic_err(ContractError::TooManyVotes { max: MAX_BATCH_VOTES }.to_string()));
+    }
+
+    let mut messages = vec![];
+    for signed in votes.iter() {
+        let signer = verify_signed_vote(&deps.api, &env.block.chain_id, &env.contract.address, signed)?;
+
+        // each signature is only accepted once
+        let key = [member_key(&deps.storage, &deps.api, &signer)?.as_slice(), &signed.nonce.to_be_bytes()].concat();
+        if vote_nonces_read(&deps.storage).may_load(&key)?.is_some() {
+            return Err(StdError::generic_err(ContractError::NonceUsed { nonce: signed.nonce }.to_string()));
+        }
+        vote_nonces(&mut deps.storage).save(&key, &true)?;
+
+        // counted as if the member voted
+        let mut signer_env = env.clone();
+        signer_env.message.sender = signer;
+        signer_env.message.sent_funds = vec![];
+        messages.extend(execute_vote(deps, signer_env, signed.proposal_id, signed.vote)?.messages);
+    }
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![
+            log("action", "submit_signed_votes"),
+            log("sender", env.message.sender),
+            log("voted", votes.len())],
+        data: None,
+    })
+}
+
 pub fn execute_execute<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     proposal_id: u64,
+    step: Option<u32>,
 ) -> Result<HandleResponse, StdError> {
     // anyone can trigger this if the vote passed
 
     let mut prop = proposals_read(&deps.storage).load(&proposal_id.to_le_bytes())?;
     // we allow execution even after the proposal "expiration" as long as all vote come in before
     // that point. If it was approved on time, it can be executed any time.
-    if prop.status != Status::Passed {
+    if ![Status::Passed, Status::PartiallyExecuted].contains(&prop.status) {
         return Err(StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
     }
+    if prop.execute_at.is_some_and(|execute_at| !execute_at.is_expired(&env.block)) {
+        return Err(StdError::generic_err(ContractError::NotDue {}.to_string()));
+    }
+    check_dependencies_executed(&deps.storage, &prop.depends_on)?;
+    let steps = prop.steps_to_execute(step)?;
+    let step_msgs: Vec<CosmosMsg> = steps.iter().map(|step| prop.msgs[*step].clone()).collect();
+    check_not_paused(&deps.storage, &env.block, &step_msgs, &env.contract.address, ContractError::ExecutePaused {})?;
+    check_not_imported(&deps.storage, proposal_id)?;
+
+    let messages = execute_steps(&mut deps.storage, &env.contract.address, proposal_id, &mut prop, &steps)?;
+
+    let mut logs = vec![
+        log("action","execute"),
+        log("sender", env.message.sender),
+        log("proposal_id", proposal_id.to_string())];
+    if let Some(step) = step {
+        logs.push(log("step", step.to_string()));
+    }
+    Ok(HandleResponse {
+        messages,
+        log: logs,
+        data: None
+    })
+}
 
-    // set it to executed
-    prop.status = Status::Executed;
-    proposals(&mut deps.storage).save(&proposal_id.to_le_bytes(), &prop)?;
+pub fn execute_execute_due<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> Result<HandleResponse, StdError> {
+    // anyone can trigger this, proposals are only executed once due and passed
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let queue = due_queue_read(&deps.storage).may_load()?.unwrap_or_default();
+
+    // start right after `start_after`, or from the beginning if it is not queued. A
+    // proposal whose messages fail reverts the whole call, so callers skip it this way.
+    let start = start_after
+        .and_then(|id| queue.iter().position(|due| due.proposal_id == id))
+        .map_or(0, |pos| pos + 1);
+
+    let mut messages = vec![];
+    let mut executed = vec![];
+    for due in queue.iter().skip(start).filter(|due| due.execute_at.is_expired(&env.block)) {
+        if executed.len() >= limit {
+            break;
+        }
+        let key = due.proposal_id.to_le_bytes();
+        let mut prop = match proposals_read(&deps.storage).may_load(&key)? {
+            Some(prop) => prop,
+            None => {
+                remove_due_proposal(&mut deps.storage, due.proposal_id)?;
+                continue;
+            }
+        };
+        match prop.status {
+            Status::Passed | Status::PartiallyExecuted => {
+                // a pause or a dependency not executed yet leaves it queued
+                let steps = prop.steps_to_execute(None)?;
+                let step_msgs: Vec<CosmosMsg> = steps.iter().map(|step| prop.msgs[*step].clone()).collect();
+                let paused = check_not_paused(
+                    &deps.storage,
+                    &env.block,
+                    &step_msgs,
+                    &env.contract.address,
+                    ContractError::ExecutePaused {},
+                );
+                let ready = check_dependencies_executed(&deps.storage, &prop.depends_on);
+                if paused.is_ok() && ready.is_ok() {
+                    messages.extend(execute_steps(
+                        &mut deps.storage,
+                        &env.contract.address,
+                        due.proposal_id,
+                        &mut prop,
+                        &steps,
+                    )?);
+                    executed.push(due.proposal_id.to_string());
+                }
+            }
+            Status::Rejected | Status::Executed => remove_due_proposal(&mut deps.storage, due.proposal_id)?,
+            // still being voted on
+            Status::Pending | Status::Open => {}
+        }
+    }
 
-    // dispatch all proposed messages
     Ok(HandleResponse {
-        messages: prop.msgs,
+        messages,
         log: vec![
-            log("action","execute"),
+            log("action", "execute_due"),
             log("sender", env.message.sender),
-            log("proposal_id", proposal_id.to_string())],
+            log("executed", executed.join(","))],
         data: None
     })
 }
 
+/// Executes `steps` of a passed proposal, returning the messages to dispatch: those of the
+/// steps, followed by the deposit refund and notifications
+fn execute_steps<S: Storage>(
+    storage: &mut S,
+    contract: &HumanAddr,
+    proposal_id: u64,
+    prop: &mut Proposal,
+    steps: &[usize],
+) -> StdResult<Vec<CosmosMsg>> {
+    let messages = execution_msgs(storage, contract, proposal_id, prop, steps)?;
+
+    // set it to executed, or partially executed while steps are left
+    prop.mark_executed(steps);
+    proposals(storage).save(&proposal_id.to_le_bytes(), prop)?;
+    if prop.status == Status::Executed {
+        remove_due_proposal(storage, proposal_id)?;
+    }
+    Ok(messages)
+}
+
 pub fn execute_close<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     proposal_id: u64,
 ) -> Result<HandleResponse<Empty>, StdError> {
     // anyone can trigger this if the vote passed
+    check_not_imported(&deps.storage, proposal_id)?;
 
     let mut prop = proposals_read(&deps.storage).load(&proposal_id.to_le_bytes())?;
-    if [Status::Executed, Status::Rejected, Status::Passed]
-        .iter()
-        .any(|x| *x == prop.status)
-    {
+    if [Status::Executed, Status::Rejected, Status::Passed].contains(&prop.status) {
         return Err(StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
     }
     if !prop.expires.is_expired(&env.block) {
         return Err(StdError::generic_err(ContractError::NotExpired {}.to_string()));
     }
 
-    // set it to failed
+    // set it to failed. A partially executed proposal abandons its remaining steps, so one
+    // that always fails doesn't hold the deposit forever
+    let old_status = prop.status;
     prop.status = Status::Rejected;
 
     proposals(&mut deps.storage).save(&proposal_id.to_le_bytes(), &prop)?;
+    remove_open_proposal(&mut deps.storage, proposal_id)?;
+    remove_due_proposal(&mut deps.storage, proposal_id)?;
+    let mut messages = settle_deposit(&mut deps.storage, &env.contract.address, proposal_id, &prop)?;
+    messages.extend(status_change_msgs(&deps.storage, &env.contract.address, proposal_id, &prop, old_status)?);
+    messages.extend(reject_dependents(&mut deps.storage, &env.contract.address, proposal_id)?);
 
     Ok(HandleResponse {
-        messages: vec![],
+        messages,
         log: vec![
             log("action","close"),
             log("sender", env.message.sender),
@@ -244,799 +825,3669 @@ pub fn execute_close<S: Storage, A: Api, Q: Querier>(
     })
 }
 
-// Queries and query functions
-
-pub fn query<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    msg: QueryMsg
-) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Threshold {} => to_binary(&query_threshold(deps)?),
-        QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, proposal_id)?),
-        QueryMsg::Vote { proposal_id, voter } => to_binary(&query_vote(deps, proposal_id, voter)?),
-        QueryMsg::ListProposals { start_after, limit } => {
-            to_binary(&list_proposals(deps, start_after, limit)?)
-        },
-        QueryMsg::ReverseProposals {
-            start_before,
-            limit,
-        } => to_binary(&reverse_proposals(deps, start_before, limit)?),
-        QueryMsg::ListVotes {
-            proposal_id,
-            start_after,
-            limit,
-        } => to_binary(&list_votes(deps, proposal_id, start_after, limit)?),
-        QueryMsg::Voter { address } => to_binary(&query_voter(deps, address)?),
-        QueryMsg::ListVoters { start_after, limit } => {
-            to_binary(&list_voters(deps, start_after, limit)?)
+/// Casts `vote` on `prop` with the weight of the members that delegated to `voter` and did
+/// not vote on the proposal themselves, each in its own house. Returns the total weight cast
+/// and the delegated votes to record with `save_delegated_votes`, so they are not counted
+/// twice if they vote later. Nothing is written, so the vote can still fail after it.
+fn cast_delegated_weight<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    block: &BlockInfo,
+    proposal_id: u64,
+    prop: &mut Proposal,
+    voter: &str,
+    vote: Vote,
+) -> StdResult<(u64, Vec<(String, DelegatedVote)>)> {
+    let mut delegated_weight = 0;
+    let mut delegated_votes = vec![];
+    for delegator in delegators_read(storage).may_load(voter.as_bytes())?.unwrap_or_default() {
+        let delegation = delegations_read(storage).load(delegator.as_bytes())?;
+        let delegator_key = member_key(storage, api, &HumanAddr::from(delegator.as_str()))?;
+        if !delegation.is_active(block)
+            || ballots_read(storage, proposal_id).may_load(delegator_key.as_slice())?.is_some()
+            || delegated_votes_read(storage, proposal_id).may_load(delegator.as_bytes())?.is_some()
+        {
+            continue;
         }
+        let weight = voters_read(storage).may_load(delegator_key.as_slice())?.unwrap_or(0);
+        let delegated = DelegatedVote {
+            delegate: voter.to_string(),
+            weight,
+        };
+        let group = voter_groups_read(storage).may_load(delegator.as_bytes())?;
+        prop.add_vote(group.as_deref(), vote, weight)?;
+        delegated_weight = add_weight(delegated_weight, weight)?;
+        delegated_votes.push((delegator, delegated));
     }
+    Ok((delegated_weight, delegated_votes))
 }
 
-fn query_threshold<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<ThresholdResponse> {
-    let cfg = config_read(&deps.storage).load()?;
-    Ok(cfg.threshold.to_response(cfg.total_weight))
+/// Records the delegated votes returned by `cast_delegated_weight`
+fn save_delegated_votes<S: Storage>(
+    storage: &mut S,
+    proposal_id: u64,
+    votes: &[(String, DelegatedVote)],
+) -> StdResult<()> {
+    for (delegator, delegated) in votes {
+        delegated_votes(storage, proposal_id).save(delegator.as_bytes(), delegated)?;
+    }
+    Ok(())
 }
 
-fn query_proposal<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, id: u64) -> StdResult<ProposalResponse> {
-    let prop = proposals_read(&deps.storage).load(&id.to_le_bytes())?;
+// sum of two vote weights, failing instead of overflowing
+fn add_weight(weight: u64, other: u64) -> StdResult<u64> {
+    weight
+        .checked_add(other)
+        .ok_or_else(|| StdError::generic_err(ContractError::TallyOverflow {}.to_string()))
+}
 
-    // TODO Uncomment this line once block info is available to queries
-    // let status = prop.current_status(&env.block);
+pub fn execute_delegate<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    to: String,
+    until: Option<Expiration>,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // only members with voting power can delegate it
+    let delegator = env.message.sender.to_string();
+    let delegator_key = member_key(&deps.storage, &deps.api, &env.message.sender)?;
+    match voters_read(&deps.storage).may_load(delegator_key.as_slice())? {
+        Some(weight) if weight >= 1 => {}
+        _ => return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string())),
+    }
+    let to = normalize_address(&deps.api, &to)?;
+    if to == delegator {
+        return Err(StdError::generic_err(ContractError::SelfDelegation {}.to_string()));
+    }
+    let to_key = member_key(&deps.storage, &deps.api, &HumanAddr::from(to.as_str()))?;
+    if voters_read(&deps.storage).may_load(to_key.as_slice())?.is_none() {
+        return Err(StdError::generic_err(ContractError::InvalidDelegate {}.to_string()));
+    }
+    let until = until.unwrap_or_default();
+    if until.is_expired(&env.block) {
+        return Err(StdError::generic_err(ContractError::DelegationExpired {}.to_string()));
+    }
 
-    let threshold = prop.threshold.to_response(prop.total_weight);
-    Ok(ProposalResponse {
-        id,
-        title: prop.title,
-        description: prop.description,
-        msgs: prop.msgs,
-        status: prop.status, //using status from last save (it may have expired since then)
-        expires: prop.expires,
-        threshold,
+    set_delegation(&mut deps.storage, &delegator, &Delegation { to: to.clone(), until })?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "delegate"),
+            log("sender", env.message.sender),
+            log("to", to)],
+        data: None
     })
 }
 
-// settings for pagination
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
-
-fn list_proposals<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    start_after: Option<u64>,
-    limit: Option<u32>,
-) -> StdResult<ProposalListResponse> {
-    let latest_prop = proposal_count_read(&deps.storage).load()?;
-
-    let limit: u64 = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT).into();
-    let limit = limit.min(latest_prop);
-    let start = start_after.unwrap_or(1);
-    
-    let mut proposals: Vec<ProposalResponse> = vec![];
-    let mut i = start;
-    while i <= limit {
-        let prop = proposals_read(&deps.storage).load(&i.to_le_bytes())?;
-        let threshold = prop.threshold.to_response(prop.total_weight);
-        let prop_response = ProposalResponse {
-            id: i,
-            title: prop.title,
-            description: prop.description,
-            msgs: prop.msgs,
-            status: prop.status, //using status from last save (it may have expired since then)
-            expires: prop.expires,
-            threshold,
-        };
-        proposals.push(prop_response);
-        i = i+1;
+pub fn execute_undelegate<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> Result<HandleResponse<Empty>, StdError> {
+    if remove_delegation(&mut deps.storage, &env.message.sender.to_string())?.is_none() {
+        return Err(StdError::generic_err(ContractError::NotDelegated {}.to_string()));
     }
 
-    Ok(ProposalListResponse { proposals })
-}
-
-fn reverse_proposals<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    start_before: Option<u64>,
-    limit: Option<u32>,
-) -> StdResult<ProposalListResponse> {
-    let latest_prop = proposal_count_read(&deps.storage).load()?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "undelegate"),
+            log("sender", env.message.sender)],
+        data: None
+    })
+}
 
-    let limit: u64 = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT).into();
-    let limit = limit.min(latest_prop) + 1;
-    let start = start_before.unwrap_or(latest_prop).min(latest_prop);
+pub fn execute_pause<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    until: Option<Expiration>,
+) -> Result<HandleResponse<Empty>, StdError> {
+    let cfg = config_read(&deps.storage).load()?;
+    let guardian = env.message.sender.to_string();
+    if !cfg.guardians.contains(&guardian) {
+        return Err(StdError::generic_err(ContractError::NotGuardian {}.to_string()));
+    }
+    // a guardian overruled by the members can't pause right after being unpaused
+    let overruled = overruled_guardians_read(&deps.storage).may_load()?.unwrap_or_default();
+    if overruled.contains(&guardian) {
+        return Err(StdError::generic_err(ContractError::GuardianOverruled {}.to_string()));
+    }
+    let until = until.unwrap_or_default();
+    if until.is_expired(&env.block) {
+        return Err(StdError::generic_err(ContractError::PauseExpired {}.to_string()));
+    }
 
-    let mut proposals: Vec<ProposalResponse> = vec![];
-    let mut i = start;
-    for _n in 1..limit {
-        let prop = proposals_read(&deps.storage).load(&i.to_le_bytes())?;
-        let threshold = prop.threshold.to_response(prop.total_weight);
-        let prop_response = ProposalResponse {
-            id: i,
-            title: prop.title,
-            description: prop.description,
-            msgs: prop.msgs,
-            status: prop.status, //using status from last save (it may have expired since then)
-            expires: prop.expires,
-            threshold,
+    // pausing again replaces the previous pause
+    pause(&mut deps.storage).save(&Pause { guardian, until })?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "pause"),
+            log("sender", env.message.sender),
+            log("until", until)],
+        data: None
+    })
+}
+
+pub fn execute_unpause<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // only an executed proposal can unpause
+    if env.message.sender != env.contract.address {
+        return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+    }
+    let current = match pause_read(&deps.storage).may_load()? {
+        Some(current) if current.is_active(&env.block) => current,
+        _ => return Err(StdError::generic_err(ContractError::NotPaused {}.to_string())),
+    };
+    pause(&mut deps.storage).remove();
+
+    let mut overruled = overruled_guardians_read(&deps.storage).may_load()?.unwrap_or_default();
+    if !overruled.contains(&current.guardian) {
+        overruled.push(current.guardian.clone());
+    }
+    overruled_guardians(&mut deps.storage).save(&overruled)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "unpause"),
+            log("guardian", current.guardian)],
+        data: None
+    })
+}
+
+pub fn execute_update_guardians<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    guardians: Vec<String>,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // only an executed proposal can change the guardians
+    if env.message.sender != env.contract.address {
+        return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+    }
+    let guardians = normalize_addresses(&deps.api, guardians)?;
+    config(&mut deps.storage).update(|mut cfg| {
+        cfg.guardians = guardians;
+        Ok(cfg)
+    })?;
+    overruled_guardians(&mut deps.storage).save(&vec![])?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("action", "update_guardians")],
+        data: None
+    })
+}
+
+pub fn execute_prune<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    before_id: u64,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // only an executed proposal can prune
+    if env.message.sender != env.contract.address {
+        return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+    }
+
+    let voters = voters_list_read(&deps.storage).load()?;
+    let end = before_id.min(proposal_count_read(&deps.storage).load()? + 1);
+    // skip the ids pruned by earlier calls, the first proposal that could not be pruned
+    // then holds the start back until it can be
+    let start = prune_start_read(&deps.storage).may_load()?.unwrap_or(1);
+    let mut next_start = start;
+    let mut pruned = 0u64;
+    for id in start..end {
+        let prop = proposals_read(&deps.storage).may_load(&id.to_le_bytes())?;
+        if matches!(&prop, Some(prop) if !can_prune(prop)) {
+            continue;
+        }
+        if id == next_start {
+            next_start += 1;
+        }
+        // already pruned proposals are no longer in the bucket
+        let prop = match prop {
+            Some(prop) => prop,
+            None => continue,
         };
-        proposals.push(prop_response);
-        i = i-1;
+        archived_proposals(&mut deps.storage).save(&id.to_le_bytes(), &ArchivedProposal::new(&prop))?;
+        proposals(&mut deps.storage).remove(&id.to_le_bytes());
+        dependents(&mut deps.storage).remove(&id.to_le_bytes());
+        deposits(&mut deps.storage).remove(&id.to_le_bytes());
+        for voter in voters.iter() {
+            let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(voter.addr.as_str()))?;
+            ballots(&mut deps.storage, id).remove(key.as_slice());
+            delegated_votes(&mut deps.storage, id).remove(voter.addr.as_bytes());
+        }
+        pruned += 1;
     }
+    prune_start(&mut deps.storage).save(&next_start)?;
 
-    Ok(ProposalListResponse { proposals })
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "prune"),
+            log("pruned", pruned)],
+        data: None
+    })
 }
 
-fn query_vote<S: Storage, A: Api, Q: Querier>(
+pub fn execute_import_state<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    snapshot: StateSnapshot,
+) -> Result<HandleResponse<Empty>, StdError> {
+    let mut status = check_import_open(deps, &env)?;
+    // version 1 snapshots are version 2 ones without pruned proposals
+    if snapshot.version == 0 || snapshot.version > SNAPSHOT_VERSION {
+        return Err(StdError::generic_err(ContractError::UnsupportedSnapshot {}.to_string()));
+    }
+
+    // the snapshot must come from a multisig with the same members
+    let cfg = config_read(&deps.storage).load()?;
+    let mut ours = voters_list_read(&deps.storage).load()?;
+    let mut theirs = snapshot.voters;
+    ours.sort_by(|a, b| a.addr.cmp(&b.addr));
+    theirs.sort_by(|a, b| a.addr.cmp(&b.addr));
+    if ours != theirs || snapshot.config.total_weight != cfg.total_weight {
+        return Err(StdError::generic_err(ContractError::SnapshotMismatch {}.to_string()));
+    }
+
+    for entry in snapshot.proposals {
+        if entry.id <= status.last_imported_id {
+            return Err(StdError::generic_err(ContractError::SnapshotOutOfOrder {}.to_string()));
+        }
+        if let Some(proposal) = entry.proposal {
+            proposals(&mut deps.storage).save(&entry.id.to_le_bytes(), &proposal)?;
+        }
+        if let Some(archived) = entry.archived {
+            archived_proposals(&mut deps.storage).save(&entry.id.to_le_bytes(), &archived)?;
+        }
+        if let Some(deposit) = entry.deposit {
+            deposits(&mut deps.storage).save(&entry.id.to_le_bytes(), &deposit)?;
+        }
+        for ballot in entry.ballots {
+            let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(ballot.voter))?;
+            if voters_read(&deps.storage).may_load(key.as_slice())?.is_none() {
+                return Err(StdError::generic_err(ContractError::SnapshotUnknownVoter {}.to_string()));
+            }
+            ballots(&mut deps.storage, entry.id).save(key.as_slice(), &ballot.ballot)?;
+        }
+        status.last_imported_id = entry.id;
+    }
+    // the state changed, so it must be approved again
+    status.approvals.clear();
+    import_status(&mut deps.storage).save(&status)?;
+
+    // new proposals get ids after the imported ones
+    let last_imported_id = status.last_imported_id;
+    proposal_count(&mut deps.storage).update(|count| Ok(count.max(last_imported_id)))?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "import_state"),
+            log("sender", env.message.sender),
+            log("last_imported_id", last_imported_id)],
+        data: None
+    })
+}
+
+pub fn execute_finish_import<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> Result<HandleResponse<Empty>, StdError> {
+    let mut status = check_import_open(deps, &env)?;
+    let sender = env.message.sender.to_string();
+    if !status.approvals.contains(&sender) {
+        status.approvals.push(sender);
+    }
+
+    // the import closes once every initial voter with voting power approved it
+    let voters = voters_list_read(&deps.storage).load()?;
+    status.importing = voters
+        .iter()
+        .any(|v| v.weight > 0 && !status.approvals.contains(&v.addr));
+    import_status(&mut deps.storage).save(&status)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "finish_import"),
+            log("sender", env.message.sender),
+            log("finished", !status.importing)],
+        data: None
+    })
+}
+
+/// Returns the import status if the import is open and the sender is one of the initial
+/// voters with voting power
+fn check_import_open<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
+    env: &Env,
+) -> StdResult<ImportStatus> {
+    let status = import_status_read(&deps.storage).load()?;
+    if !status.importing {
+        return Err(StdError::generic_err(ContractError::ImportClosed {}.to_string()));
+    }
+    let key = member_key(&deps.storage, &deps.api, &env.message.sender)?;
+    match voters_read(&deps.storage).may_load(key.as_slice())? {
+        Some(weight) if weight > 0 => Ok(status),
+        _ => Err(StdError::generic_err(ContractError::Unauthorized {}.to_string())),
+    }
+}
+
+/// Validates `addr` and returns it in its normalized form
+fn normalize_address<A: Api>(api: &A, addr: &str) -> StdResult<String> {
+    let canonical = api.canonical_address(&HumanAddr::from(addr))?;
+    Ok(api.human_address(&canonical)?.to_string())
+}
+
+fn normalize_addresses<A: Api>(api: &A, addrs: Vec<String>) -> StdResult<Vec<String>> {
+    addrs.iter().map(|addr| normalize_address(api, addr)).collect()
+}
+
+/// Fails for proposals imported from a previous deployment, which are read-only
+fn check_not_imported<S: Storage>(storage: &S, proposal_id: u64) -> StdResult<()> {
+    if import_status_read(storage).may_load()?.is_some_and(|status| status.is_imported(proposal_id)) {
+        return Err(StdError::generic_err(ContractError::ImportedProposal {}.to_string()));
+    }
+    Ok(())
+}
+
+pub fn execute_add_hook<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+    code_hash: String,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // hooks are governed, only an executed proposal can change them
+    if env.message.sender != env.contract.address {
+        return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+    }
+
+    let mut list = hooks_read(&deps.storage).may_load()?.unwrap_or_default();
+    if list.iter().any(|hook| hook.address == address) {
+        return Err(StdError::generic_err(ContractError::HookAlreadyRegistered {}.to_string()));
+    }
+    list.push(Hook { address: address.clone(), code_hash });
+    hooks(&mut deps.storage).save(&list)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "add_hook"),
+            log("hook", address)],
+        data: None
+    })
+}
+
+pub fn execute_remove_hook<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // hooks are governed, only an executed proposal can change them. Hooks are not
+    // notified of those proposals, so a failing hook does not block its removal.
+    if env.message.sender != env.contract.address {
+        return Err(StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+    }
+
+    let mut list = hooks_read(&deps.storage).may_load()?.unwrap_or_default();
+    let len = list.len();
+    list.retain(|hook| hook.address != address);
+    if list.len() == len {
+        return Err(StdError::generic_err(ContractError::HookNotRegistered {}.to_string()));
+    }
+    hooks(&mut deps.storage).save(&list)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "remove_hook"),
+            log("hook", address)],
+        data: None
+    })
+}
+
+/// Releases the deposit held for a proposal that was just executed or rejected, returning
+/// the refund message if the refund policy gives it back to the proposer. The record is
+/// kept as the proposal's history; its final status marks it as settled.
+fn settle_deposit<S: Storage>(
+    storage: &mut S,
+    contract: &HumanAddr,
     proposal_id: u64,
-    voter: String
-) -> StdResult<VoteResponse> {
-    // TODO: Implement address validation
+    prop: &Proposal,
+) -> StdResult<Vec<CosmosMsg>> {
+    let deposit = match deposits_read(storage).may_load(&proposal_id.to_le_bytes())? {
+        Some(deposit) => deposit,
+        None => return Ok(vec![]),
+    };
 
-    let ballot = ballots_read(&deps.storage, proposal_id).may_load(voter.as_bytes())?;
-    let vote = ballot.map(|b| VoteInfo {
-        proposal_id,
-        voter: voter.into(),
-        vote: b.vote,
-        weight: b.weight,
-    });
-    Ok(VoteResponse { vote })
+    if deposit.refund_policy.should_refund(prop.status, &prop.votes) {
+        Ok(vec![deposit.refund_msg(contract)?])
+    } else {
+        Ok(vec![])
+    }
 }
 
-fn list_votes<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
+/// Rejects the proposals that can no longer be executed because `proposal_id` was rejected,
+/// settling their deposits. Dependents that already passed are rejected too, so under
+/// `OnlyPassed` their deposit is forfeited like that of any proposal never executed.
+fn reject_dependents<S: Storage>(
+    storage: &mut S,
+    contract: &HumanAddr,
     proposal_id: u64,
-    _start_after: Option<String>,
-    _limit: Option<u32>,
-) -> StdResult<VoteListResponse> {
-    // Currently no use for start_after or limit 
-    // Returns every vote 
-    let voters = voters_list_read(&deps.storage).load()?;
-    let mut votes: Vec<VoteInfo> = Vec::new();
-    for voter in voters {
-        let ballot = ballots_read(&deps.storage, proposal_id).may_load(&voter.addr.as_bytes()).unwrap();
-        if ballot.is_some() {
-            let vote_info = VoteInfo {
-                proposal_id,
-                voter: voter.addr,
-                vote: ballot.unwrap().vote,
-                weight: voter.weight,
-            };
-            votes.push(vote_info);
-        } 
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = vec![];
+    for id in pending_dependents(storage, proposal_id)? {
+        let mut prop = proposals_read(storage).load(&id.to_le_bytes())?;
+        let old_status = prop.status;
+        prop.status = Status::Rejected;
+        proposals(storage).save(&id.to_le_bytes(), &prop)?;
+        remove_open_proposal(storage, id)?;
+        remove_due_proposal(storage, id)?;
+        messages.extend(settle_deposit(storage, contract, id, &prop)?);
+        messages.extend(status_change_msgs(storage, contract, id, &prop, old_status)?);
     }
-    Ok(VoteListResponse { votes })
+    Ok(messages)
 }
 
-fn query_voter<S: Storage, A: Api, Q: Querier>(
+// Queries and query functions
+
+pub fn query<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
-    voter: String
-) -> StdResult<VoterResponse> {
-    // TODO: Implement address validation
-    let weight = voters_read(&deps.storage).may_load(&voter.as_bytes())?;
-    Ok(VoterResponse { weight })
+    msg: QueryMsg
+) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Threshold {} => to_binary(&query_threshold(deps)?),
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::ContractInfo {} => to_binary(&query_contract_info()),
+        QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, proposal_id)?),
+        QueryMsg::Vote { proposal_id, voter } => to_binary(&query_vote(deps, proposal_id, voter)?),
+        QueryMsg::ListProposals { start_after, limit } => {
+            to_binary(&list_proposals(deps, start_after, limit)?)
+        },
+        QueryMsg::ReverseProposals {
+            start_before,
+            limit,
+        } => to_binary(&reverse_proposals(deps, start_before, limit)?),
+        QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_binary(&list_votes(deps, proposal_id, start_after, limit)?),
+        QueryMsg::Voter { address } => to_binary(&query_voter(deps, address)?),
+        QueryMsg::ListVoters { start_after, limit } => {
+            to_binary(&list_voters(deps, start_after, limit)?)
+        }
+        QueryMsg::Delegation { address } => to_binary(&query_delegation(deps, address)?),
+        QueryMsg::VotingPower {
+            proposal_id,
+            address,
+        } => to_binary(&query_voting_power(deps, proposal_id, address)?),
+        QueryMsg::Deposit { proposal_id } => to_binary(&query_deposit(deps, proposal_id)?),
+        QueryMsg::PendingForVoter { address } => to_binary(&pending_for_voter(deps, address)?),
+        QueryMsg::Hooks {} => to_binary(&query_hooks(deps)?),
+        QueryMsg::ExportState { start_after, limit } => {
+            to_binary(&export_state(deps, start_after, limit)?)
+        }
+        QueryMsg::SimulateExecute { proposal_id } => {
+            to_binary(&simulate_execute(deps, proposal_id)?)
+        }
+        QueryMsg::ParentProposal {
+            parent,
+            proposal_id,
+        } => to_binary(&parent.query_proposal(&deps.querier, proposal_id)?),
+    }
+}
+
+fn query_threshold<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<ThresholdResponse> {
+    let cfg = config_read(&deps.storage).load()?;
+    let threshold = cfg.threshold.to_response(cfg.total_weight);
+    if cfg.houses.is_empty() {
+        return Ok(threshold);
+    }
+    let voters = voters_list_read(&deps.storage).load()?;
+    Ok(ThresholdResponse::MultiHouse {
+        threshold: Box::new(threshold),
+        houses: cfg
+            .houses
+            .iter()
+            .map(|house| HouseThresholdResponse {
+                group: house.group.clone(),
+                threshold: house.threshold.to_response(house.total_weight(&voters)),
+            })
+            .collect(),
+    })
+}
+
+fn query_config<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<ConfigResponse> {
+    let cfg = config_read(&deps.storage).load()?;
+    let proposal_count = proposal_count_read(&deps.storage).load()?;
+    let voter_count = voters_list_read(&deps.storage).load()?.len() as u64;
+    Ok(ConfigResponse {
+        threshold: cfg.threshold,
+        total_weight: cfg.total_weight,
+        max_voting_period: cfg.max_voting_period,
+        proposers: cfg.proposers,
+        proposal_deposit: cfg.proposal_deposit,
+        threshold_rules: cfg.threshold_rules,
+        houses: cfg.houses,
+        guardians: cfg.guardians,
+        pause: pause_read(&deps.storage).may_load()?,
+        proposal_count,
+        voter_count,
+        version: CONTRACT_VERSION.to_string(),
+    })
+}
+
+fn query_contract_info() -> ContractInfoResponse {
+    ContractInfoResponse {
+        contract: CONTRACT_NAME.to_string(),
+        version: CONTRACT_VERSION.to_string(),
+    }
+}
+
+fn query_proposal<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, id: u64) -> StdResult<ProposalResponse> {
+    // TODO Uncomment this line once block info is available to queries
+    // let status = prop.current_status(&env.block);
+
+    load_proposal_response(&deps.storage, id)
+}
+
+/// ProposalResponse of a proposal, or its summary form if it was pruned
+fn load_proposal_response<S: Storage>(storage: &S, id: u64) -> StdResult<ProposalResponse> {
+    match may_load_proposal_response(storage, id)? {
+        Some(response) => Ok(response),
+        // same error as a missing proposal
+        None => archived_proposals_read(storage).load(&id.to_le_bytes()).map(|archived| archived.into_response(id)),
+    }
+}
+
+/// Like `load_proposal_response`, but None for ids in neither store, like those an import
+/// left out
+fn may_load_proposal_response<S: Storage>(storage: &S, id: u64) -> StdResult<Option<ProposalResponse>> {
+    let mut response = match proposals_read(storage).may_load(&id.to_le_bytes())? {
+        Some(prop) => {
+            let mut response = map_proposal(id, prop);
+            response.deposit = deposits_read(storage).may_load(&id.to_le_bytes())?.map(|d| d.info());
+            response
+        }
+        None => match archived_proposals_read(storage).may_load(&id.to_le_bytes())? {
+            Some(archived) => archived.into_response(id),
+            None => return Ok(None),
+        },
+    };
+    response.dependents = dependents_read(storage).may_load(&id.to_le_bytes())?.unwrap_or_default();
+    Ok(Some(response))
 }
 
-fn list_voters<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    _start_after: Option<String>,
-    _limit: Option<u32>,
-) -> StdResult<VoterListResponse> {
-    // Currently no use for start_after or limit 
-    // Returns the full list of voters
-    let voters = voters_list_read(&deps.storage).load()?;
-    Ok(VoterListResponse { voters })
-}
+fn map_proposal(id: u64, prop: Proposal) -> ProposalResponse {
+    let threshold = prop.threshold_response();
+    ProposalResponse {
+        id,
+        title: prop.title,
+        description: prop.description,
+        msgs: prop.msgs,
+        status: prop.status, //using status from last save (it may have expired since then)
+        expires: prop.expires,
+        threshold,
+        proposer: prop.proposer,
+        deposit: None,
+        start_height: prop.start_height,
+        start_time: prop.start_time,
+        execute_at: prop.execute_at,
+        depends_on: prop.depends_on,
+        dependents: vec![],
+        steps: prop.steps,
+        archived: None,
+    }
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+fn list_proposals<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let latest_prop = proposal_count_read(&deps.storage).load()?;
+
+    let limit: u64 = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT).into();
+    let limit = limit.min(latest_prop);
+    let start = start_after.unwrap_or(1);
+    
+    let mut proposals: Vec<ProposalResponse> = vec![];
+    let mut i = start;
+    while i <= limit {
+        proposals.extend(may_load_proposal_response(&deps.storage, i)?);
+        i += 1;
+    }
+
+    Ok(ProposalListResponse { proposals })
+}
+
+fn reverse_proposals<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let latest_prop = proposal_count_read(&deps.storage).load()?;
+
+    let limit: u64 = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT).into();
+    let limit = limit.min(latest_prop) + 1;
+    let start = start_before.unwrap_or(latest_prop).min(latest_prop);
+
+    let mut proposals: Vec<ProposalResponse> = vec![];
+    let mut i = start;
+    for _n in 1..limit {
+        if i == 0 {
+            break;
+        }
+        proposals.extend(may_load_proposal_response(&deps.storage, i)?);
+        i -= 1;
+    }
+
+    Ok(ProposalListResponse { proposals })
+}
+
+fn query_vote<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    proposal_id: u64,
+    voter: String
+) -> StdResult<VoteResponse> {
+    let voter = normalize_address(&deps.api, &voter)?;
+    let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(voter.as_str()))?;
+    let ballot = ballots_read(&deps.storage, proposal_id).may_load(key.as_slice())?;
+    let vote = ballot.map(|b| VoteInfo {
+        proposal_id,
+        voter,
+        vote: b.vote,
+        weight: b.weight,
+    });
+    Ok(VoteResponse { vote })
+}
+
+fn list_votes<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    proposal_id: u64,
+    _start_after: Option<String>,
+    _limit: Option<u32>,
+) -> StdResult<VoteListResponse> {
+    // Currently no use for start_after or limit 
+    // Returns every vote 
+    let voters = voters_list_read(&deps.storage).load()?;
+    let mut votes: Vec<VoteInfo> = Vec::new();
+    for voter in voters {
+        let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(voter.addr.as_str()))?;
+        let ballot = ballots_read(&deps.storage, proposal_id).may_load(key.as_slice())?;
+        if let Some(ballot) = ballot {
+            let vote_info = VoteInfo {
+                proposal_id,
+                voter: voter.addr,
+                vote: ballot.vote,
+                weight: ballot.weight,
+            };
+            votes.push(vote_info);
+        } 
+    }
+    Ok(VoteListResponse { votes })
+}
+
+fn query_voter<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    voter: String
+) -> StdResult<VoterResponse> {
+    let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(voter))?;
+    let weight = voters_read(&deps.storage).may_load(key.as_slice())?;
+    Ok(VoterResponse { weight })
+}
+
+fn query_hooks<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<HooksResponse> {
+    let hooks = hooks_read(&deps.storage).may_load()?.unwrap_or_default();
+    Ok(HooksResponse { hooks })
+}
+
+fn query_delegation<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: String,
+) -> StdResult<DelegationResponse> {
+    let address = normalize_address(&deps.api, &address)?;
+    let delegation = delegations_read(&deps.storage).may_load(address.as_bytes())?;
+    Ok(DelegationResponse { delegation })
+}
+
+fn query_voting_power<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    proposal_id: u64,
+    address: String,
+) -> StdResult<VotingPowerResponse> {
+    let address = normalize_address(&deps.api, &address)?;
+    let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(address.as_str()))?;
+    let weight = voters_read(&deps.storage).may_load(key.as_slice())?.unwrap_or(0);
+    let ballot = ballots_read(&deps.storage, proposal_id).may_load(key.as_slice())?;
+
+    let mut delegated = vec![];
+    for delegator in delegators_read(&deps.storage).may_load(address.as_bytes())?.unwrap_or_default() {
+        let delegator_key = member_key(&deps.storage, &deps.api, &HumanAddr::from(delegator.as_str()))?;
+        let counted = match delegated_votes_read(&deps.storage, proposal_id).may_load(delegator.as_bytes())? {
+            Some(delegated_vote) => delegated_vote.delegate == address,
+            // delegations granted after the address voted are not counted
+            None => {
+                ballot.is_none()
+                    && ballots_read(&deps.storage, proposal_id).may_load(delegator_key.as_slice())?.is_none()
+            }
+        };
+        if counted {
+            let delegation = delegations_read(&deps.storage).load(delegator.as_bytes())?;
+            let delegator_weight = voters_read(&deps.storage).may_load(delegator_key.as_slice())?.unwrap_or(0);
+            delegated.push(DelegatedWeight {
+                delegator,
+                weight: delegator_weight,
+                until: delegation.until,
+            });
+        }
+    }
+
+    // once voted, the ballot holds what was actually cast
+    let total = match ballot {
+        Some(ballot) => ballot.weight,
+        None => weight + delegated.iter().map(|d| d.weight).sum::<u64>(),
+    };
+    Ok(VotingPowerResponse {
+        weight,
+        delegated,
+        total,
+    })
+}
+
+fn simulate_execute<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    proposal_id: u64,
+) -> StdResult<SimulateExecuteResponse> {
+    let mut prop = proposals_read(&deps.storage).load(&proposal_id.to_le_bytes())?;
+    if [Status::Executed, Status::Rejected].contains(&prop.status) {
+        return Err(StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
+    }
+    // open proposals are simulated as if they passed
+    if prop.status == Status::Open {
+        prop.status = Status::Passed;
+    }
+
+    // every step not executed yet
+    let steps = prop.steps_to_execute(None)?;
+    let contract = contract_address_read(&deps.storage)
+        .may_load()?
+        .ok_or_else(|| StdError::generic_err(ContractError::UnknownContractAddress {}.to_string()))?;
+    let msgs = execution_msgs(&deps.storage, &contract, proposal_id, &prop, &steps)?;
+    let balance = deps.querier.query_all_balances(contract)?;
+    simulate(msgs, balance)
+}
+
+fn query_deposit<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    proposal_id: u64,
+) -> StdResult<DepositResponse> {
+    // the record outlives the settlement, and imported ones were paid to the old contract
+    let held = match proposals_read(&deps.storage).may_load(&proposal_id.to_le_bytes())? {
+        Some(prop) => {
+            prop.status != Status::Executed
+                && prop.status != Status::Rejected
+                && check_not_imported(&deps.storage, proposal_id).is_ok()
+        }
+        None => false,
+    };
+    let deposit = if held {
+        deposits_read(&deps.storage).may_load(&proposal_id.to_le_bytes())?
+    } else {
+        None
+    };
+    Ok(DepositResponse { deposit })
+}
+
+fn pending_for_voter<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: String,
+) -> StdResult<ProposalListResponse> {
+    // only members with voting power, their own or delegated to them, have anything to sign
+    let address = normalize_address(&deps.api, &address)?;
+    let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(address.as_str()))?;
+    let weight = voters_read(&deps.storage).may_load(key.as_slice())?.unwrap_or(0);
+    let delegators = delegators_read(&deps.storage).may_load(address.as_bytes())?.unwrap_or_default();
+    if weight == 0 && delegators.is_empty() {
+        return Ok(ProposalListResponse { proposals: vec![] });
+    }
+
+    // walk the open proposals index rather than the whole proposal history
+    let open = open_proposal_ids(&deps.storage)?;
+    let mut proposals: Vec<ProposalResponse> = vec![];
+    for id in open {
+        if ballots_read(&deps.storage, id).may_load(key.as_slice())?.is_some() {
+            continue;
+        }
+        // the delegated weight may already be cast, by the delegators or a former delegate
+        if weight == 0 && query_voting_power(deps, id, address.clone())?.total == 0 {
+            continue;
+        }
+        proposals.push(load_proposal_response(&deps.storage, id)?);
+    }
+
+    Ok(ProposalListResponse { proposals })
+}
+
+fn export_state<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<StateSnapshot> {
+    let proposal_count = proposal_count_read(&deps.storage).load()?;
+    let voters = voters_list_read(&deps.storage).load()?;
+
+    let limit: u64 = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT).into();
+    let start = start_after.unwrap_or(0) + 1;
+    let end = start.saturating_add(limit).min(proposal_count + 1);
+
+    let mut snapshots = vec![];
+    for id in start..end {
+        // pruned proposals are carried over as their summary
+        let proposal = proposals_read(&deps.storage).may_load(&id.to_le_bytes())?;
+        let archived = match proposal {
+            Some(_) => None,
+            None => archived_proposals_read(&deps.storage).may_load(&id.to_le_bytes())?,
+        };
+        if proposal.is_none() && archived.is_none() {
+            continue;
+        }
+        let mut entries = vec![];
+        for voter in voters.iter() {
+            let key = member_key(&deps.storage, &deps.api, &HumanAddr::from(voter.addr.as_str()))?;
+            if let Some(ballot) = ballots_read(&deps.storage, id).may_load(key.as_slice())? {
+                entries.push(BallotSnapshot {
+                    voter: voter.addr.clone(),
+                    ballot,
+                });
+            }
+        }
+        snapshots.push(ProposalSnapshot {
+            id,
+            proposal,
+            archived,
+            deposit: deposits_read(&deps.storage).may_load(&id.to_le_bytes())?,
+            ballots: entries,
+        });
+    }
+
+    Ok(StateSnapshot {
+        version: SNAPSHOT_VERSION,
+        contract_version: CONTRACT_VERSION.to_string(),
+        config: config_read(&deps.storage).load()?,
+        voters,
+        proposal_count,
+        proposals: snapshots,
+    })
+}
+
+fn list_voters<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    _start_after: Option<String>,
+    _limit: Option<u32>,
+) -> StdResult<VoterListResponse> {
+    // Currently no use for start_after or limit 
+    // Returns the full list of voters
+    let voters = voters_list_read(&deps.storage).load()?;
+    Ok(VoterListResponse {
+        voters: voters.into_iter().map(VoterDetail::from).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coin, from_slice, BankMsg, MessageInfo, QuerierResult, QueryRequest, WasmQuery};
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+
+    use crate::deposit::{Denom, DepositInfo, DepositRefundPolicy, ProposalDeposit};
+    use crate::house::House;
+    use crate::expiration::Duration;
+    use crate::rules::{ActionCategory, ThresholdRule};
+    use crate::threshold::{Threshold, ThresholdError};
+    use crate::math::Decimal;
+    use crate::msg::InitCallback;
+    use crate::nested::ParentMultisig;
+    use crate::pause::Pause;
+    use crate::signed::{pubkey_address, SignDoc};
+    use crate::state::open_proposals_read;
+
+    use super::*;
+
+    fn mock_env_height(height_delta: u64) -> Env {
+        let mut env = mock_env(OWNER, &[]);
+        env.block.height += height_delta;
+        env
+    }
+
+    fn mock_env_time(time_delta: u64) -> Env {
+        let mut env = mock_env(OWNER, &[]);
+        env.block.time += time_delta;
+        env
+    }
+
+    const OWNER: &str = "admin0001";
+    const VOTER1: &str = "voter0001";
+    const VOTER2: &str = "voter0002";
+    const VOTER3: &str = "voter0003";
+    const VOTER4: &str = "voter0004";
+    const VOTER5: &str = "voter0005";
+    const NOWEIGHT_VOTER: &str = "voterxxxx";
+    const SOMEBODY: &str = "somebody";
+
+    fn voter<T: Into<String>>(addr: T, weight: u64) -> Voter {
+        Voter {
+            addr: addr.into(),
+            weight,
+            group: None,
+        }
+    }
+
+    // this will set up the instantiation for other tests
+    #[track_caller]
+    fn setup_test_case<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        info: MessageInfo,
+        threshold: Threshold,
+        max_voting_period: Duration,
+    ) -> Result<InitResponse<Empty>, StdError> {
+        // Instantiate a contract with voters
+        let voters = vec![
+            voter(info.sender.to_string(), 1),
+            voter(VOTER1, 1),
+            voter(VOTER2, 2),
+            voter(VOTER3, 3),
+            voter(VOTER4, 4),
+            voter(VOTER5, 5),
+            voter(NOWEIGHT_VOTER, 0),
+        ];
+
+        let init_msg = InitMsg {
+            voters,
+            threshold,
+            max_voting_period,
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        init(deps, mock_env(OWNER, &[]), init_msg)
+    }
+
+    fn get_tally<S: Storage, A: Api, Q: Querier>(deps: &Extern<S,A,Q>, proposal_id: u64) -> u64 {
+        // Get all the voters on the proposal
+        let voters = QueryMsg::ListVotes {
+            proposal_id,
+            start_after: None,
+            limit: None,
+        };
+        let votes: VoteListResponse =
+            from_binary(&query(deps, voters).unwrap()).unwrap();
+        // Sum the weights of the Yes votes to get the tally
+        votes
+            .votes
+            .iter()
+            .filter(|&v| v.vote == Vote::Yes)
+            .map(|v| v.weight)
+            .sum()
+    }
+
+    #[test]
+    fn test_init_works() {
+        let mut deps = mock_dependencies(20,&[]);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+
+        let max_voting_period = Duration::Time(1234567);
+
+        // No voters fails
+        let init_msg = InitMsg {
+            voters: vec![],
+            threshold: Threshold::ThresholdQuorum {
+                threshold: Decimal::zero(),
+                quorum: Decimal::percent(1),
+            },
+            max_voting_period,
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        let err = init(
+            &mut deps,
+            mock_env(OWNER, &[]),
+            init_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NoVoters {}.to_string()));
+
+        // Zero required weight fails
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1)],
+            ..init_msg
+        };
+        let err =
+            init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(ContractError::Threshold(ThresholdError::InvalidThreshold {}).to_string())
+        );
+
+        // Total weight less than required weight not allowed
+        let threshold = Threshold::AbsoluteCount { weight: 100 };
+        let err =
+            setup_test_case(&mut deps, info.clone(), threshold, max_voting_period).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(ContractError::Threshold(ThresholdError::UnreachableWeight {}).to_string())
+        );
+
+        // All valid
+        let threshold = Threshold::AbsoluteCount { weight: 1 };
+        setup_test_case(&mut deps, info.clone(), threshold, max_voting_period).unwrap();
+
+    }
+
+    #[test]
+    fn zero_weight_member_cant_vote() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 4 };
+        let voting_period = Duration::Time(2000000);
+
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, threshold, voting_period).unwrap();
+
+        let bank_msg = BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
+            to_address: SOMEBODY.into(),
+            amount: vec![coin(1, "BTC")],
+        };
+        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+
+        // Voter without voting power still can create proposal
+        let proposal = HandleMsg::Propose {
+            title: "Rewarding somebody".to_string(),
+            description: "Do we reward her?".to_string(),
+            msgs,
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let res = handle( &mut deps, mock_env(NOWEIGHT_VOTER, &[]), proposal).unwrap();
+
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        // Cast a No vote
+        let no_vote = HandleMsg::Vote {
+            proposal_id,
+            vote: Vote::No,
+        };
+        // Only voters with weight can vote
+        let err = handle(&mut deps, mock_env(NOWEIGHT_VOTER, &[]), no_vote).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+    }
+
+    #[test]
+    fn test_propose_works() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 4 };
+        let voting_period = Duration::Time(2000000);
+
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+
+        let bank_msg = BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
+            to_address: SOMEBODY.into(),
+            amount: vec![coin(1, "BTC")],
+        };
+        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+
+        // Only voters can propose
+        let proposal = HandleMsg::Propose {
+            title: "Rewarding somebody".to_string(),
+            description: "Do we reward her?".to_string(),
+            msgs: msgs.clone(),
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), proposal.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+
+        // Wrong expiration option fails
+        let proposal_wrong_exp = HandleMsg::Propose {
+            title: "Rewarding somebody".to_string(),
+            description: "Do we reward her?".to_string(),
+            msgs,
+            latest: Some(Expiration::AtHeight(123456)),
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let err = handle(&mut deps, mock_env(OWNER, &[]), proposal_wrong_exp).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongExpiration {}.to_string()));
+
+        // Proposal from voter works
+        let res = handle( &mut deps, mock_env(VOTER3, &[]), proposal.clone()).unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","propose"),
+                    log("sender", VOTER3),
+                    log("proposal_id", 1u8.to_string()),
+                    log("status", "Open")],
+                data: None
+            }
+        );
+
+        // Proposal from voter with enough vote power directly passes
+        let res = handle(&mut deps, mock_env(VOTER4, &[]), proposal).unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","propose"),
+                    log("sender", VOTER4),
+                    log("proposal_id", 2u8.to_string()),
+                    log("status", "Passed")],
+                data: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_vote_works() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Time(2000000);
+
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+
+        // Propose
+        let bank_msg = BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
+            to_address: SOMEBODY.into(),
+            amount: vec![coin(1, "BTC")],
+        };
+        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+        let proposal = HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            msgs,
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        // Owner cannot vote (again)
+        let yes_vote = HandleMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        };
+        let err = handle(&mut deps, mock_env(OWNER, &[]), yes_vote.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::AlreadyVoted {}.to_string()));
+
+        // Only voters can vote
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), yes_vote.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+
+        // But voter1 can
+        let res = handle(&mut deps, mock_env(VOTER1, &[]), yes_vote.clone()).unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER1),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Open")],
+                data: None
+            }
+        );
+
+        // No/Veto votes have no effect on the tally
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        // Compute the current tally
+        let tally = get_tally(&deps, proposal_id);
+
+        // Cast a No vote
+        let no_vote = HandleMsg::Vote {
+            proposal_id,
+            vote: Vote::No,
+        };
+        handle(&mut deps, mock_env(VOTER2, &[]), no_vote.clone()).unwrap();
+
+        // Cast a Veto vote
+        let veto_vote = HandleMsg::Vote {
+            proposal_id,
+            vote: Vote::Veto,
+        };
+        handle(&mut deps, mock_env(VOTER3, &[]), veto_vote).unwrap();
+
+        // Verify
+        assert_eq!(tally, get_tally(&deps, proposal_id));
+
+        // Once voted, votes cannot be changed
+        let err = handle(&mut deps, mock_env(VOTER3, &[]), yes_vote.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::AlreadyVoted {}.to_string()));
+        assert_eq!(tally, get_tally(&deps, proposal_id));
+
+        // Expired proposals cannot be voted
+        let env = match voting_period {
+            Duration::Time(duration) => mock_env_time(duration + 1),
+            Duration::Height(duration) => mock_env_height(duration + 1),
+        };
+        let err = handle(&mut deps, env, no_vote).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Expired {}.to_string()));
+
+        // Vote it again, so it passes
+        let res = handle(&mut deps, mock_env(VOTER4, &[]), yes_vote.clone()).unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER4),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Passed")],
+                data: None
+            }
+        );
+
+        // non-Open proposals cannot be voted
+        let err = handle(&mut deps, mock_env(VOTER5, &[]), yes_vote).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotOpen {}.to_string()));
+
+        // Propose
+        let bank_msg = BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
+            to_address: SOMEBODY.into(),
+            amount: vec![coin(1, "BTC")],
+        };
+        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+        let proposal = HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            msgs,
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        // Cast a No vote
+        let no_vote = HandleMsg::Vote {
+            proposal_id,
+            vote: Vote::No,
+        };
+        // Voter1 vote no, weight 1
+        let res = handle(&mut deps, mock_env(VOTER1, &[]), no_vote.clone()).unwrap();
+
+        // Verify it is not enough to reject yet
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER1),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Open")],
+                data: None
+            }
+        );
+
+        // Voter 4 votes no, weight 4, total weight for no so far 5, need 14 to reject
+        let res = handle(&mut deps, mock_env(VOTER4, &[]), no_vote.clone()).unwrap();
+
+        // Verify it is still open as we actually need no votes > 16 - 3
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER4),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Open")],
+                data: None
+            }
+        );
+
+        // Voter 3 votes no, weight 3, total weight for no far 8, need 14
+        let _res = handle(&mut deps, mock_env(VOTER3, &[]), no_vote.clone()).unwrap();
+
+        // Voter 5 votes no, weight 5, total weight for no far 13, need 14
+        let res = handle(&mut deps, mock_env(VOTER5, &[]), no_vote.clone()).unwrap();
+
+        // Verify it is still open as we actually need no votes > 16 - 3
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER5),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Open")],
+                data: None
+            }
+        );
+
+        // Voter 2 votes no, weight 2, total weight for no so far 15, need 14.
+        // Can now reject
+        let res = handle(&mut deps, mock_env(VOTER2, &[]), no_vote).unwrap();
+
+        // Verify it is rejected as, 15 no votes > 16 - 3
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER2),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Rejected")],
+                data: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_execute_works() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Time(2000000);
+
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+
+        // Propose
+        let bank_msg = BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
+            to_address: SOMEBODY.into(),
+            amount: vec![coin(1, "BTC")],
+        };
+        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+        let proposal = HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            msgs: msgs.clone(),
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        // Only Passed can be executed
+        let execution = HandleMsg::Execute { proposal_id, step: None };
+        let err = handle(&mut deps, mock_env(OWNER, &[]), execution.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
+
+        // Vote it, so it passes
+        let vote = HandleMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        };
+        let res = handle(&mut deps, mock_env(VOTER3, &[]), vote).unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","vote"),
+                    log("sender", VOTER3),
+                    log("proposal_id", proposal_id.to_string()),
+                    log("status", "Passed")],
+                data: None
+            }
+        );
+
+        // In passing: Try to close Passed fails
+        let closing = HandleMsg::Close { proposal_id };
+        let err = handle(&mut deps, mock_env(VOTER3, &[]), closing).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
+
+        // Execute works. Anybody can execute Passed proposals
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), execution).unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: msgs,
+                log: vec![
+                    log("action","execute"),
+                    log("sender", SOMEBODY),
+                    log("proposal_id", proposal_id.to_string())],
+                data: None
+            }
+        );
+
+        // In passing: Try to close Executed fails
+        let closing = HandleMsg::Close { proposal_id };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), closing).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
+    }
+
+    #[test]
+    fn test_close_works() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Height(2000000);
+
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+
+        // Propose
+        let bank_msg = BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
+            to_address: SOMEBODY.into(),
+            amount: vec![coin(1, "BTC")],
+        };
+        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+        let proposal = HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            msgs: msgs.clone(),
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        let closing = HandleMsg::Close { proposal_id };
+
+        // Non-expired proposals cannot be closed
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), closing).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotExpired {}.to_string()));
+
+        // Expired proposals can be closed
+        let proposal = HandleMsg::Propose {
+            title: "(Try to) pay somebody".to_string(),
+            description: "Pay somebody after time?".to_string(),
+            msgs,
+            latest: Some(Expiration::AtHeight(123456)),
+            execute_at: None,
+            depends_on: vec![],
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        // Get the proposal id from the logs
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        let closing = HandleMsg::Close { proposal_id };
+
+        // Close expired works
+        let env = mock_env_height(1234567);
+        let res = handle(
+            &mut deps,
+            env,
+            closing.clone(),
+        )
+        .unwrap();
+
+        // Verify
+        assert_eq!(
+            res,
+            HandleResponse {
+                messages: vec![],
+                log: vec![
+                    log("action","close"),
+                    log("sender", OWNER),
+                    log("proposal_id", proposal_id.to_string())],
+                data: None
+            }
+        );
+
+        // Trying to close it again fails
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), closing).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
+    }
+
+    #[test]
+    fn test_pending_for_voter_works() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Time(2000000);
 
-#[cfg(test)]
-mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env};
-    use cosmwasm_std::{coin, coins, from_binary, BankMsg, Coin, MessageInfo, HumanAddr};
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, threshold, voting_period).unwrap();
 
-    use crate::expiration::Duration;
-    use crate::threshold::{Threshold, ThresholdError};
-    use crate::math::{Decimal, Uint128};
-    use crate::msg::Voter;
+        // Open two proposals
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal.clone()).unwrap();
+        let first_id: u64 = res.log[2].value.parse().unwrap();
+        let res = handle(&mut deps, mock_env(VOTER1, &[]), proposal).unwrap();
+        let second_id: u64 = res.log[2].value.parse().unwrap();
+
+        let pending = |deps: &Extern<_, _, _>, address: &str| -> Vec<u64> {
+            let msg = QueryMsg::PendingForVoter { address: address.to_string() };
+            let res: ProposalListResponse = from_binary(&query(deps, msg).unwrap()).unwrap();
+            res.proposals.iter().map(|p| p.id).collect()
+        };
 
-    use super::*;
+        // Proposers already voted on their own proposals
+        assert_eq!(pending(&deps, OWNER), vec![second_id]);
+        assert_eq!(pending(&deps, VOTER1), vec![first_id]);
+        assert_eq!(pending(&deps, VOTER2), vec![first_id, second_id]);
 
-    fn mock_env_height(height_delta: u64) -> Env {
-        let mut env = mock_env(OWNER, &[]);
-        env.block.height += height_delta;
-        env
-    }
+        // Non-members and zero weight members have nothing to sign
+        assert!(pending(&deps, SOMEBODY).is_empty());
+        assert!(pending(&deps, NOWEIGHT_VOTER).is_empty());
 
-    fn mock_env_time(time_delta: u64) -> Env {
-        let mut env = mock_env(OWNER, &[]);
-        env.block.time = env.block.time + time_delta;
-        env
+        // unless weight is delegated to them, and not cast yet
+        let delegate = HandleMsg::Delegate { to: NOWEIGHT_VOTER.to_string(), until: None };
+        handle(&mut deps, mock_env(VOTER4, &[]), delegate).unwrap();
+        assert_eq!(pending(&deps, NOWEIGHT_VOTER), vec![first_id, second_id]);
+        let no_vote = HandleMsg::Vote { proposal_id: second_id, vote: Vote::No };
+        handle(&mut deps, mock_env(VOTER4, &[]), no_vote).unwrap();
+        assert_eq!(pending(&deps, NOWEIGHT_VOTER), vec![first_id]);
+
+        // Passing the first proposal drops it from everybody's list
+        let yes_vote = HandleMsg::Vote {
+            proposal_id: first_id,
+            vote: Vote::Yes,
+        };
+        handle(&mut deps, mock_env(VOTER3, &[]), yes_vote).unwrap();
+        assert_eq!(pending(&deps, VOTER2), vec![second_id]);
+        assert_eq!(open_proposals_read(&deps.storage).load().unwrap(), vec![second_id]);
+
+        // Closing an expired proposal removes it from the index as well
+        let env = mock_env_time(2000001);
+        handle(&mut deps, env, HandleMsg::Close { proposal_id: second_id }).unwrap();
+        assert!(pending(&deps, VOTER2).is_empty());
+        assert!(open_proposals_read(&deps.storage).load().unwrap().is_empty());
     }
 
-    const OWNER: &str = "admin0001";
-    const VOTER1: &str = "voter0001";
-    const VOTER2: &str = "voter0002";
-    const VOTER3: &str = "voter0003";
-    const VOTER4: &str = "voter0004";
-    const VOTER5: &str = "voter0005";
-    const NOWEIGHT_VOTER: &str = "voterxxxx";
-    const SOMEBODY: &str = "somebody";
+    #[test]
+    fn test_config_query_works() {
+        let mut deps = mock_dependencies(20,&[]);
 
-    fn voter<T: Into<String>>(addr: T, weight: u64) -> Voter {
-        Voter {
-            addr: addr.into(),
-            weight,
-        }
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Time(2000000);
+
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, threshold.clone(), voting_period).unwrap();
+
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        let res: ConfigResponse =
+            from_binary(&query(&deps, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                threshold,
+                total_weight: 16,
+                max_voting_period: voting_period,
+                proposers: None,
+                proposal_deposit: None,
+                threshold_rules: vec![],
+                houses: vec![],
+                guardians: vec![],
+                pause: None,
+                proposal_count: 1,
+                voter_count: 7,
+                version: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        let res: ContractInfoResponse =
+            from_binary(&query(&deps, QueryMsg::ContractInfo {}).unwrap()).unwrap();
+        assert_eq!(res.contract, "snip3-fixed-multisig");
+        assert_eq!(res.version, CONTRACT_VERSION);
     }
 
-    // this will set up the instantiation for other tests
-    #[track_caller]
-    fn setup_test_case<S: Storage, A: Api, Q: Querier>(
-        deps: &mut Extern<S, A, Q>,
-        info: MessageInfo,
-        threshold: Threshold,
-        max_voting_period: Duration,
-    ) -> Result<InitResponse<Empty>, StdError> {
-        // Instantiate a contract with voters
+    #[test]
+    fn test_proposer_restrictions() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let max_voting_period = Duration::Time(2000000);
         let voters = vec![
-            voter(&info.sender.to_string(), 1),
+            voter(OWNER, 1),
             voter(VOTER1, 1),
             voter(VOTER2, 2),
-            voter(VOTER3, 3),
-            voter(VOTER4, 4),
-            voter(VOTER5, 5),
             voter(NOWEIGHT_VOTER, 0),
         ];
 
+        // Proposers must be members
+        let init_msg = InitMsg {
+            voters: voters.clone(),
+            threshold: threshold.clone(),
+            max_voting_period,
+            proposers: Some(vec![VOTER1.to_string(), SOMEBODY.to_string()]),
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        let err = init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::UnknownProposer {}.to_string()));
+
+        // Zero weight members can be proposers
         let init_msg = InitMsg {
             voters,
             threshold,
             max_voting_period,
+            proposers: Some(vec![VOTER1.to_string(), NOWEIGHT_VOTER.to_string()]),
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        init(deps, mock_env(OWNER, &[]), init_msg)
+        init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
+
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+
+        // Members outside of the proposers list cannot propose
+        let err = handle(&mut deps, mock_env(VOTER2, &[]), proposal.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+
+        // Listed members can, and are recorded on the proposal
+        let env = mock_env(NOWEIGHT_VOTER, &[]);
+        let res = handle(&mut deps, env.clone(), proposal).unwrap();
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        let prop: ProposalResponse = from_binary(
+            &query(&deps, QueryMsg::Proposal { proposal_id }).unwrap()
+        ).unwrap();
+        assert_eq!(prop.proposer, NOWEIGHT_VOTER);
+        assert_eq!(prop.start_height, env.block.height);
+        assert_eq!(prop.start_time, env.block.time);
     }
 
-    fn get_tally<S: Storage, A: Api, Q: Querier>(deps: &Extern<S,A,Q>, proposal_id: u64) -> u64 {
-        // Get all the voters on the proposal
-        let voters = QueryMsg::ListVotes {
-            proposal_id,
-            start_after: None,
-            limit: None,
+    fn setup_deposit_case<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        token: DepositToken,
+        refund_policy: DepositRefundPolicy,
+    ) -> InitResponse<Empty> {
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(VOTER2, 2)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: Some(ProposalDeposit {
+                token,
+                amount: Uint128(100),
+                refund_policy,
+            }),
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        let votes: VoteListResponse =
-            from_binary(&query(deps, voters).unwrap()).unwrap();
-        // Sum the weights of the Yes votes to get the tally
-        votes
-            .votes
-            .iter()
-            .filter(|&v| v.vote == Vote::Yes)
-            .map(|v| v.weight)
-            .sum()
+        init(deps, mock_env(OWNER, &[]), init_msg).unwrap()
     }
 
     #[test]
-    fn test_init_works() {
-        let mut deps = mock_dependencies(6,&[]);
-        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+    fn test_native_deposit_works() {
+        let mut deps = mock_dependencies(20,&[]);
+        let token = DepositToken::Native { denom: "uscrt".to_string() };
+        setup_deposit_case(&mut deps, token, DepositRefundPolicy::OnlyPassed);
 
-        let max_voting_period = Duration::Time(1234567);
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
 
-        // No voters fails
-        let init_msg = InitMsg {
-            voters: vec![],
-            threshold: Threshold::ThresholdQuorum {
-                threshold: Decimal::zero(),
-                quorum: Decimal::percent(1),
-            },
-            max_voting_period,
+        // The deposit is required and must match exactly
+        let err = handle(&mut deps, mock_env(OWNER, &[]), proposal.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DepositRequired {}.to_string()));
+        let err = handle(&mut deps, mock_env(OWNER, &[coin(50, "uscrt")]), proposal.clone())
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidDeposit {}.to_string()));
+
+        // A passed and executed proposal is refunded
+        let res = handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal.clone())
+            .unwrap();
+        let passed_id: u64 = res.log[2].value.parse().unwrap();
+        let deposit: DepositResponse = from_binary(
+            &query(&deps, QueryMsg::Deposit { proposal_id: passed_id }).unwrap()
+        ).unwrap();
+        assert_eq!(deposit.deposit.unwrap().depositor, HumanAddr::from(OWNER));
+
+        let vote = HandleMsg::Vote { proposal_id: passed_id, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER1, &[]), vote).unwrap();
+        let res = handle(
+            &mut deps,
+            mock_env(SOMEBODY, &[]),
+            HandleMsg::Execute { proposal_id: passed_id, step: None },
+        ).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                to_address: HumanAddr::from(OWNER),
+                amount: vec![coin(100, "uscrt")],
+            })]
+        );
+        let deposit: DepositResponse = from_binary(
+            &query(&deps, QueryMsg::Deposit { proposal_id: passed_id }).unwrap()
+        ).unwrap();
+        assert_eq!(deposit.deposit, None);
+
+        // A rejected proposal forfeits it
+        let res = handle(&mut deps, mock_env(VOTER1, &[coin(100, "uscrt")]), proposal).unwrap();
+        let rejected_id: u64 = res.log[2].value.parse().unwrap();
+        let vote = HandleMsg::Vote { proposal_id: rejected_id, vote: Vote::No };
+        handle(&mut deps, mock_env(VOTER2, &[]), vote.clone()).unwrap();
+        let res = handle(&mut deps, mock_env(OWNER, &[]), vote).unwrap();
+        assert_eq!(res.log[3], log("status", "Rejected"));
+        assert!(res.messages.is_empty());
+        let deposit: DepositResponse = from_binary(
+            &query(&deps, QueryMsg::Deposit { proposal_id: rejected_id }).unwrap()
+        ).unwrap();
+        assert_eq!(deposit.deposit, None);
+
+        // settled deposits are still reported with their proposals
+        let prop: ProposalResponse = from_binary(
+            &query(&deps, QueryMsg::Proposal { proposal_id: rejected_id }).unwrap()
+        ).unwrap();
+        assert_eq!(
+            prop.deposit,
+            Some(DepositInfo {
+                amount: Uint128(100),
+                denom: Denom::Native("uscrt".to_string()),
+                refund_failed_proposals: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_passed_dependent_rejected_with_dependency() {
+        let mut deps = mock_dependencies(20,&[]);
+        let token = DepositToken::Native { denom: "uscrt".to_string() };
+        setup_deposit_case(&mut deps, token, DepositRefundPolicy::OnlyPassed);
+
+        let pay = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(1, "uscrt")],
+        });
+        let proposal = |depends_on: Vec<u64>| HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            msgs: vec![pay.clone()],
+            latest: None,
+            execute_at: None,
+            depends_on,
         };
-        let err = init(
+        handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal(vec![])).unwrap();
+        let res = handle(&mut deps, mock_env(VOTER2, &[coin(100, "uscrt")]), proposal(vec![1])).unwrap();
+        assert_eq!(res.log[3], log("status", "Passed"));
+
+        // closing the dependency rejects the passed dependent, which is never executed,
+        // so neither deposit is refunded
+        let mut env = mock_env(SOMEBODY, &[]);
+        env.block.time += 2000000;
+        let res = handle(&mut deps, env, HandleMsg::Close { proposal_id: 1 }).unwrap();
+        assert!(res.messages.is_empty());
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 2 }).unwrap()).unwrap();
+        assert_eq!(prop.status, Status::Rejected);
+        let deposit: DepositResponse =
+            from_binary(&query(&deps, QueryMsg::Deposit { proposal_id: 2 }).unwrap()).unwrap();
+        assert_eq!(deposit.deposit, None);
+    }
+
+    #[test]
+    fn test_snip20_deposit_works() {
+        let mut deps = mock_dependencies(20,&[]);
+        let token_addr = HumanAddr::from("token");
+        let token = DepositToken::Snip20 {
+            address: token_addr.clone(),
+            code_hash: "token_hash".to_string(),
+        };
+        let res = setup_deposit_case(&mut deps, token, DepositRefundPolicy::Always);
+
+        // We register with the token to get Receive callbacks
+        assert_eq!(res.messages.len(), 1);
+
+        // Plain proposals cannot pay a token deposit
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        let err = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DepositRequired {}.to_string()));
+
+        let receive_from = |sender: &str, from: &str, amount: u128| HandleMsg::Receive {
+            sender: HumanAddr::from(sender),
+            from: HumanAddr::from(from),
+            amount: Uint128(amount),
+            msg: Some(to_binary(&ReceiveMsg::ProposeAction {
+                title: "Pay somebody".to_string(),
+                description: "Do I pay her?".to_string(),
+                action: ProposalAction::Signal {},
+                latest: None,
+                execute_at: None,
+            }).unwrap()),
+        };
+        let receive = |amount: u128| receive_from(OWNER, OWNER, amount);
+
+        // Only the configured token can call Receive, with the right amount
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), receive(100)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidDeposit {}.to_string()));
+        let err = handle(&mut deps, mock_env(token_addr.clone(), &[]), receive(10)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidDeposit {}.to_string()));
+
+        // The sender of the tokens is the proposer
+        let res = handle(&mut deps, mock_env(token_addr.clone(), &[]), receive(100)).unwrap();
+        assert_eq!(res.log[1], log("sender", OWNER));
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        // A spender sending a member's tokens proposes on its own account, not the member's
+        let err = handle(&mut deps, mock_env(token_addr.clone(), &[]), receive_from(SOMEBODY, OWNER, 100)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        let res = handle(&mut deps, mock_env(token_addr, &[]), receive_from(VOTER1, SOMEBODY, 100)).unwrap();
+        assert_eq!(res.log[1], log("sender", VOTER1));
+        let spent_id: u64 = res.log[2].value.parse().unwrap();
+        assert_eq!(get_tally(&deps, spent_id), 1);
+        let deposit: DepositResponse = from_binary(
+            &query(&deps, QueryMsg::Deposit { proposal_id: spent_id }).unwrap()
+        ).unwrap();
+        assert_eq!(deposit.deposit.unwrap().depositor, HumanAddr::from(SOMEBODY));
+
+        // Closing an expired proposal refunds it under the Always policy
+        let res = handle(
             &mut deps,
-            mock_env(OWNER, &[]),
-            init_msg.clone(),
-        )
-        .unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::NoVoters {}.to_string()));
+            mock_env_time(2000001),
+            HandleMsg::Close { proposal_id },
+        ).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
 
-        // Zero required weight fails
+    #[test]
+    fn test_tiered_thresholds() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let large_send = ThresholdRule {
+            category: ActionCategory::BankSend {
+                denom: Some("uscrt".to_string()),
+                min_amount: Some(Uint128(1000)),
+                max_amount: None,
+            },
+            threshold: Threshold::AbsoluteCount { weight: 4 },
+        };
         let init_msg = InitMsg {
-            voters: vec![voter(OWNER, 1)],
-            ..init_msg
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(VOTER2, 1), voter(VOTER3, 1), voter(VOTER4, 1)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: Some(vec![large_send]),
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        init(&mut deps, mock_env(OWNER, &[]), init_msg.clone()).unwrap();
+
+        let propose = |deps: &mut Extern<_, _, _>, amount: u128| -> ProposalResponse {
+            let msgs = vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: MOCK_CONTRACT_ADDR.into(),
+                to_address: SOMEBODY.into(),
+                amount: vec![coin(amount, "uscrt")],
+            })];
+            let proposal = HandleMsg::Propose {
+                title: "Pay somebody".to_string(),
+                description: "Do I pay her?".to_string(),
+                msgs,
+                latest: None,
+                execute_at: None,
+                depends_on: vec![],
+            };
+            let res = handle(deps, mock_env(OWNER, &[]), proposal).unwrap();
+            let proposal_id: u64 = res.log[2].value.parse().unwrap();
+            from_binary(&query(deps, QueryMsg::Proposal { proposal_id }).unwrap()).unwrap()
         };
-        let err =
-            init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap_err();
-        assert_eq!(
-            err,
-            StdError::generic_err(ContractError::Threshold(ThresholdError::InvalidThreshold {}).to_string())
-        );
 
-        // Total weight less than required weight not allowed
-        let threshold = Threshold::AbsoluteCount { weight: 100 };
-        let err =
-            setup_test_case(&mut deps, info.clone(), threshold, max_voting_period).unwrap_err();
+        // small transfers use the default 2 of 5
+        let prop = propose(&mut deps, 10);
+        assert_eq!(prop.threshold, ThresholdResponse::AbsoluteCount { weight: 2, total_weight: 5 });
+
+        // large ones need 4 of 5
+        let prop = propose(&mut deps, 5000);
+        assert_eq!(prop.threshold, ThresholdResponse::AbsoluteCount { weight: 4, total_weight: 5 });
+
+        // rules are validated like the default threshold
+        let mut deps = mock_dependencies(20,&[]);
+        let init_msg = InitMsg {
+            threshold_rules: Some(vec![ThresholdRule {
+                category: ActionCategory::SelfGovernance {},
+                threshold: Threshold::AbsoluteCount { weight: 6 },
+            }]),
+            ..init_msg
+        };
+        let err = init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap_err();
         assert_eq!(
             err,
             StdError::generic_err(ContractError::Threshold(ThresholdError::UnreachableWeight {}).to_string())
         );
+    }
 
-        // All valid
-        let threshold = Threshold::AbsoluteCount { weight: 1 };
-        setup_test_case(&mut deps, info.clone(), threshold, max_voting_period).unwrap();
 
+    #[test]
+    fn test_delegation_works() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let threshold = Threshold::AbsoluteCount { weight: 10 };
+        let voting_period = Duration::Time(2000000);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, threshold, voting_period).unwrap();
+
+        let delegate = |to: &str, until: Option<Expiration>| HandleMsg::Delegate {
+            to: to.to_string(),
+            until,
+        };
+
+        // invalid delegations
+        let err = handle(&mut deps, mock_env(VOTER1, &[]), delegate(VOTER1, None)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::SelfDelegation {}.to_string()));
+        let err = handle(&mut deps, mock_env(VOTER1, &[]), delegate(SOMEBODY, None)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidDelegate {}.to_string()));
+        let err = handle(&mut deps, mock_env(NOWEIGHT_VOTER, &[]), delegate(VOTER2, None)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        let err = handle(&mut deps, mock_env(VOTER1, &[]), delegate(VOTER2, Some(Expiration::AtHeight(1)))).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DelegationExpired {}.to_string()));
+        let err = handle(&mut deps, mock_env(VOTER4, &[]), HandleMsg::Undelegate {}).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotDelegated {}.to_string()));
+
+        // VOTER1 and VOTER3 delegate to VOTER2
+        handle(&mut deps, mock_env(VOTER1, &[]), delegate(VOTER2, None)).unwrap();
+        handle(&mut deps, mock_env(VOTER3, &[]), delegate(VOTER2, None)).unwrap();
+        let res: DelegationResponse = from_binary(
+            &query(&deps, QueryMsg::Delegation { address: VOTER1.to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.delegation, Some(Delegation { to: VOTER2.to_string(), until: Expiration::Never {} }));
+
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+
+        let power = |deps: &Extern<_, _, _>, address: &str| -> VotingPowerResponse {
+            let query_msg = QueryMsg::VotingPower {
+                proposal_id,
+                address: address.to_string(),
+            };
+            from_binary(&query(deps, query_msg).unwrap()).unwrap()
+        };
+        let res = power(&deps, VOTER2);
+        assert_eq!(res.weight, 2);
+        assert_eq!(res.delegated.len(), 2);
+        assert_eq!(res.total, 6);
+
+        // the delegate votes with the delegated weight
+        let yes_vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER2, &[]), yes_vote).unwrap();
+        assert_eq!(get_tally(&deps, proposal_id), 7);
+
+        // a delegator voting directly takes its weight back from the delegate
+        let no_vote = HandleMsg::Vote { proposal_id, vote: Vote::No };
+        handle(&mut deps, mock_env(VOTER3, &[]), no_vote).unwrap();
+        assert_eq!(get_tally(&deps, proposal_id), 4);
+        let res = power(&deps, VOTER2);
+        assert_eq!(res.delegated.len(), 1);
+        assert_eq!(res.total, 3);
+
+        // the delegate can't vote again with what is left
+        let yes_vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        let err = handle(&mut deps, mock_env(VOTER2, &[]), yes_vote).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::AlreadyVoted {}.to_string()));
+
+        // and a delegator whose weight was cast can't have it cast twice through a new delegate
+        handle(&mut deps, mock_env(VOTER1, &[]), delegate(VOTER4, None)).unwrap();
+        let res = power(&deps, VOTER4);
+        assert!(res.delegated.is_empty());
+        assert_eq!(res.total, 4);
+        let yes_vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER4, &[]), yes_vote).unwrap();
+        assert_eq!(get_tally(&deps, proposal_id), 8);
+
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Undelegate {}).unwrap();
+        let res: DelegationResponse = from_binary(
+            &query(&deps, QueryMsg::Delegation { address: VOTER1.to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.delegation, None);
     }
 
+
     #[test]
-    fn zero_weight_member_cant_vote() {
-        let mut deps = mock_dependencies(6,&[]);
+    fn test_multi_house_approval() {
+        let mut deps = mock_dependencies(20,&[]);
 
-        let threshold = Threshold::AbsoluteCount { weight: 4 };
-        let voting_period = Duration::Time(2000000);
+        let member = |addr: &str, weight: u64, group: Option<&str>| Voter {
+            addr: addr.to_string(),
+            weight,
+            group: group.map(|g| g.to_string()),
+        };
+        let house = |group: &str, weight: u64| House {
+            group: group.to_string(),
+            threshold: Threshold::AbsoluteCount { weight },
+        };
+        let init_msg = InitMsg {
+            voters: vec![
+                member(OWNER, 1, Some("board")),
+                member(VOTER1, 1, Some("board")),
+                member(VOTER2, 2, Some("tech")),
+                member(VOTER3, 3, Some("tech")),
+                member(VOTER4, 4, None),
+            ],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: Some(vec![house("board", 2), house("tech", 3)]),
+            import_state: None,
+            callback: None,
+        };
+
+        // houses must be reachable by their own members
+        let mut bad_msg = init_msg.clone();
+        bad_msg.houses = Some(vec![house("board", 3)]);
+        let err = init(&mut deps, mock_env(OWNER, &[]), bad_msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ThresholdError::UnreachableWeight {}.to_string()));
+
+        init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
+
+        let res: ThresholdResponse = from_binary(&query(&deps, QueryMsg::Threshold {}).unwrap()).unwrap();
+        let expected = ThresholdResponse::MultiHouse {
+            threshold: Box::new(ThresholdResponse::AbsoluteCount { weight: 2, total_weight: 11 }),
+            houses: vec![
+                HouseThresholdResponse {
+                    group: "board".to_string(),
+                    threshold: ThresholdResponse::AbsoluteCount { weight: 2, total_weight: 2 },
+                },
+                HouseThresholdResponse {
+                    group: "tech".to_string(),
+                    threshold: ThresholdResponse::AbsoluteCount { weight: 3, total_weight: 5 },
+                },
+            ],
+        };
+        assert_eq!(res, expected);
+
+        let propose = |deps: &mut Extern<_, _, _>, proposer: &str| -> u64 {
+            let proposal = HandleMsg::ProposeAction {
+                title: "Upgrade".to_string(),
+                description: "Needs both houses".to_string(),
+                action: ProposalAction::Signal {},
+                latest: None,
+                execute_at: None,
+            };
+            let res = handle(deps, mock_env(proposer, &[]), proposal).unwrap();
+            res.log[2].value.parse().unwrap()
+        };
+        let vote = |deps: &mut Extern<_, _, _>, voter: &str, proposal_id: u64, vote: Vote| -> String {
+            let res = handle(deps, mock_env(voter, &[]), HandleMsg::Vote { proposal_id, vote }).unwrap();
+            res.log[3].value.clone()
+        };
+
+        let proposal_id = propose(&mut deps, OWNER);
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id }).unwrap()).unwrap();
+        assert_eq!(prop.threshold, expected);
+
+        // the overall threshold is met, but neither house approved yet
+        assert_eq!(vote(&mut deps, VOTER4, proposal_id, Vote::Yes), "Open");
+        // the board approves
+        assert_eq!(vote(&mut deps, VOTER1, proposal_id, Vote::Yes), "Open");
+        // and so does tech
+        assert_eq!(vote(&mut deps, VOTER3, proposal_id, Vote::Yes), "Passed");
+
+        // a single house can reject a proposal
+        let proposal_id = propose(&mut deps, VOTER2);
+        assert_eq!(vote(&mut deps, VOTER4, proposal_id, Vote::Yes), "Open");
+        assert_eq!(vote(&mut deps, VOTER3, proposal_id, Vote::No), "Rejected");
+    }
+
+
+    #[test]
+    fn test_hooks_notified() {
+        let mut deps = mock_dependencies(20,&[]);
 
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Time(2000000);
         let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
         setup_test_case(&mut deps, info, threshold, voting_period).unwrap();
 
-        let bank_msg = BankMsg::Send {
-            from_address: OWNER.into(),
-            to_address: SOMEBODY.into(),
-            amount: vec![coin(1, "BTC")],
+        let add_hook = HandleMsg::AddHook {
+            address: HumanAddr::from("dashboard"),
+            code_hash: "dashboard_hash".to_string(),
         };
-        let msgs = vec![CosmosMsg::Bank(bank_msg)];
 
-        // Voter without voting power still can create proposal
-        let proposal = HandleMsg::Propose {
-            title: "Rewarding somebody".to_string(),
-            description: "Do we reward her?".to_string(),
-            msgs,
+        // only the multisig itself can register hooks
+        let err = handle(&mut deps, mock_env(OWNER, &[]), add_hook.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), add_hook.clone()).unwrap();
+        let err = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), add_hook).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::HookAlreadyRegistered {}.to_string()));
+
+        let res: HooksResponse = from_binary(&query(&deps, QueryMsg::Hooks {}).unwrap()).unwrap();
+        let hook = Hook {
+            address: HumanAddr::from("dashboard"),
+            code_hash: "dashboard_hash".to_string(),
+        };
+        assert_eq!(res.hooks, vec![hook.clone()]);
+
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
             latest: None,
+            execute_at: None,
+        };
+        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        let new_proposal = ProposalHookMsg::NewProposal {
+            proposal_id,
+            proposer: OWNER.to_string(),
+            status: Status::Open,
         };
-        let res = handle( &mut deps, mock_env(NOWEIGHT_VOTER, &[]), proposal).unwrap();
+        assert_eq!(res.messages, vec![new_proposal.into_cosmos_msg(&hook).unwrap()]);
 
-        // Get the proposal id from the logs
-        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        // votes that leave the proposal open notify nobody
+        let vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        let res = handle(&mut deps, mock_env(VOTER1, &[]), vote).unwrap();
+        assert_eq!(res.messages, vec![]);
 
-        // Cast a No vote
-        let no_vote = HandleMsg::Vote {
+        let vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        let res = handle(&mut deps, mock_env(VOTER2, &[]), vote).unwrap();
+        let passed = ProposalHookMsg::ProposalStatusChanged {
             proposal_id,
-            vote: Vote::No,
+            old_status: Status::Open,
+            new_status: Status::Passed,
         };
-        // Only voters with weight can vote
-        let err = handle(&mut deps, mock_env(NOWEIGHT_VOTER, &[]), no_vote).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        assert_eq!(res.messages, vec![passed.into_cosmos_msg(&hook).unwrap()]);
+
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id, step: None }).unwrap();
+        let executed = ProposalHookMsg::ProposalStatusChanged {
+            proposal_id,
+            old_status: Status::Passed,
+            new_status: Status::Executed,
+        };
+        let expected = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("dashboard"),
+            callback_code_hash: "dashboard_hash".to_string(),
+            msg: Binary::from(
+                br#"{"proposal_hook":{"proposal_status_changed":{"proposal_id":1,"old_status":"passed","new_status":"executed"}}}"#.to_vec(),
+            ),
+            send: vec![],
+        });
+        assert_eq!(executed.into_cosmos_msg(&hook).unwrap(), expected);
+        assert_eq!(res.messages, vec![expected]);
+
+        let remove_hook = HandleMsg::RemoveHook { address: HumanAddr::from("dashboard") };
+        handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), remove_hook.clone()).unwrap();
+        let err = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), remove_hook).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::HookNotRegistered {}.to_string()));
     }
 
     #[test]
-    fn test_propose_works() {
-        let mut deps = mock_dependencies(6,&[]);
+    fn test_failing_hook_removed_by_proposal() {
+        let mut deps = mock_dependencies(20,&[]);
 
-        let threshold = Threshold::AbsoluteCount { weight: 4 };
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
         let voting_period = Duration::Time(2000000);
-
         let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
-        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+        setup_test_case(&mut deps, info, threshold, voting_period).unwrap();
 
-        let bank_msg = BankMsg::Send {
-            from_address: OWNER.into(),
-            to_address: SOMEBODY.into(),
-            amount: vec![coin(1, "BTC")],
+        let add_hook = HandleMsg::AddHook {
+            address: HumanAddr::from("broken"),
+            code_hash: "broken_hash".to_string(),
         };
-        let msgs = vec![CosmosMsg::Bank(bank_msg)];
-
-        // Only voters can propose
-        let proposal = HandleMsg::Propose {
-            title: "Rewarding somebody".to_string(),
-            description: "Do we reward her?".to_string(),
-            msgs: msgs.clone(),
+        handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), add_hook).unwrap();
+
+        // the chain runs the returned messages in the same transaction: one failing reverts
+        // the whole handle, as the hook contract "broken" does with every notification
+        let notifies = |res: &HandleResponse| {
+            res.messages.iter().any(|msg| match msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => contract_addr.as_str() == "broken",
+                _ => false,
+            })
+        };
+        let propose = |msgs: Vec<CosmosMsg>| HandleMsg::Propose {
+            title: "Remove broken hook".to_string(),
+            description: "It fails every notification".to_string(),
+            msgs,
             latest: None,
+            execute_at: None,
+            depends_on: vec![],
         };
-        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), proposal.clone()).unwrap_err();
+        let remove_hook = HandleMsg::RemoveHook { address: HumanAddr::from("broken") };
+        let remove_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            callback_code_hash: "multisig_hash".to_string(),
+            msg: to_binary(&remove_hook).unwrap(),
+            send: vec![],
+        });
+
+        // removing hooks stays governed
+        let err = handle(&mut deps, mock_env(VOTER1, &[]), remove_hook.clone()).unwrap_err();
         assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
 
-        // Wrong expiration option fails
-        let proposal_wrong_exp = HandleMsg::Propose {
-            title: "Rewarding somebody".to_string(),
-            description: "Do we reward her?".to_string(),
-            msgs,
-            latest: Some(Expiration::AtHeight(123456)),
-        };
-        let err = handle(&mut deps, mock_env(OWNER, &[]), proposal_wrong_exp).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::WrongExpiration {}.to_string()));
+        // other proposals notify it, even along with a removal
+        let pay = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(1, "BTC")],
+        });
+        let res = handle(&mut deps, mock_env(OWNER, &[]), propose(vec![remove_msg.clone(), pay])).unwrap();
+        assert!(notifies(&res));
 
-        // Proposal from voter works
-        let res = handle( &mut deps, mock_env(VOTER3, &[]), proposal.clone()).unwrap();
+        // but not of a proposal only removing hooks, through its whole life
+        let res = handle(&mut deps, mock_env(OWNER, &[]), propose(vec![remove_msg.clone()])).unwrap();
+        assert!(!notifies(&res));
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        let vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        let res = handle(&mut deps, mock_env(VOTER2, &[]), vote).unwrap();
+        assert_eq!(res.log[3].value, "Passed");
+        assert!(!notifies(&res));
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id, step: None }).unwrap();
+        assert_eq!(res.messages, vec![remove_msg]);
+
+        handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), remove_hook).unwrap();
+        let res: HooksResponse = from_binary(&query(&deps, QueryMsg::Hooks {}).unwrap()).unwrap();
+        assert!(res.hooks.is_empty());
+    }
 
-        // Verify
+
+    #[test]
+    fn test_init_callback() {
+        let mut deps = mock_dependencies(20,&[]);
+
+        let callback = InitCallback {
+            contract_addr: HumanAddr::from("factory"),
+            code_hash: "factory_hash".to_string(),
+            id: 1,
+        };
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1)],
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: Some(callback.clone()),
+        };
+        let res = init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
         assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","propose"),
-                    log("sender", VOTER3),
-                    log("proposal_id", 1u8.to_string()),
-                    log("status", "Open")],
-                data: None
-            }
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: callback.contract_addr,
+                callback_code_hash: callback.code_hash,
+                msg: Binary::from(br#"{"register_multisig":{"id":1}}"#.to_vec()),
+                send: vec![],
+            })]
         );
+    }
 
-        // Proposal from voter with enough vote power directly passes
-        let res = handle(&mut deps, mock_env(VOTER4, &[]), proposal).unwrap();
 
-        // Verify
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","propose"),
-                    log("sender", VOTER4),
-                    log("proposal_id", 2u8.to_string()),
-                    log("status", "Passed")],
-                data: None
+    /// answers smart queries to "parent" from the multisig it holds
+    struct ParentQuerier {
+        parent: Extern<MockStorage, MockApi, MockQuerier>,
+    }
+
+    impl Querier for ParentQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_slice(bin_request).unwrap() {
+                QueryRequest::<Empty>::Wasm(WasmQuery::Smart { contract_addr, msg, .. })
+                    if contract_addr.as_str() == "parent" =>
+                {
+                    Ok(query(&self.parent, from_binary(&msg).unwrap()))
+                }
+                _ => panic!("unexpected query"),
             }
-        );
+        }
     }
 
     #[test]
-    fn test_vote_works() {
-        let mut deps = mock_dependencies(6,&[]);
+    fn test_nested_multisig() {
+        const CHILD: &str = "child_multisig";
 
-        let threshold = Threshold::AbsoluteCount { weight: 3 };
-        let voting_period = Duration::Time(2000000);
+        // the parent has the child multisig as a member
+        let mut parent = mock_dependencies(20,&[]);
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(CHILD, 2)],
+            threshold: Threshold::AbsoluteCount { weight: 3 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        init(&mut parent, mock_env(OWNER, &[]), init_msg).unwrap();
+        let proposal = HandleMsg::ProposeAction {
+            title: "Fund the team".to_string(),
+            description: "Send the team its budget".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        let res = handle(&mut parent, mock_env(OWNER, &[]), proposal).unwrap();
+        let parent_proposal_id: u64 = res.log[2].value.parse().unwrap();
 
-        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
-        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+        let mut child = Extern {
+            storage: MockStorage::default(),
+            api: MockApi::new(20),
+            querier: ParentQuerier { parent },
+        };
+        let init_msg = InitMsg {
+            voters: vec![voter(VOTER1, 1), voter(VOTER2, 1)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        init(&mut child, mock_env(CHILD, &[]), init_msg).unwrap();
 
-        // Propose
-        let bank_msg = BankMsg::Send {
-            from_address: OWNER.into(),
+        // child members can see what they are voting on
+        let parent_multisig = ParentMultisig {
+            address: HumanAddr::from("parent"),
+            code_hash: "parent_hash".to_string(),
+        };
+        let query_msg = QueryMsg::ParentProposal {
+            parent: parent_multisig.clone(),
+            proposal_id: parent_proposal_id,
+        };
+        let res: ProposalResponse = from_binary(&query(&child, query_msg).unwrap()).unwrap();
+        assert_eq!(res.title, "Fund the team");
+        assert_eq!(res.status, Status::Open);
+
+        // the child votes on the parent proposal through one of its own
+        let proposal = HandleMsg::ProposeAction {
+            title: "Approve the budget".to_string(),
+            description: "Vote yes on the parent".to_string(),
+            action: ProposalAction::VoteOnParent {
+                parent: parent_multisig,
+                proposal_id: parent_proposal_id,
+                vote: Vote::Yes,
+            },
+            latest: None,
+            execute_at: None,
+        };
+        let res = handle(&mut child, mock_env(VOTER1, &[]), proposal).unwrap();
+        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        let yes_vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        handle(&mut child, mock_env(VOTER2, &[]), yes_vote).unwrap();
+        let mut res = handle(&mut child, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id, step: None }).unwrap();
+
+        let vote_msg = match res.messages.pop() {
+            Some(CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, callback_code_hash, msg, .. })) => {
+                assert_eq!(contract_addr, HumanAddr::from("parent"));
+                assert_eq!(callback_code_hash, "parent_hash");
+                from_binary(&msg).unwrap()
+            }
+            _ => panic!("expected a vote on the parent"),
+        };
+        assert_eq!(vote_msg, HandleMsg::Vote { proposal_id: parent_proposal_id, vote: Vote::Yes });
+
+        // which the parent counts with the child's weight
+        let res = handle(&mut child.querier.parent, mock_env(CHILD, &[]), vote_msg).unwrap();
+        assert_eq!(res.log[3].value, "Passed");
+    }
+
+
+    #[test]
+    fn test_guardian_pause() {
+        const GUARDIAN: &str = "guardian";
+        let mut deps = mock_dependencies(20,&[]);
+
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(VOTER2, 1)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: Some(vec![GUARDIAN.to_string()]),
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
+
+        let propose = |deps: &mut Extern<_, _, _>, msgs: Vec<CosmosMsg>| {
+            let proposal = HandleMsg::Propose {
+                title: "Pay somebody".to_string(),
+                description: "Do I pay her?".to_string(),
+                msgs,
+                latest: None,
+                execute_at: None,
+                depends_on: vec![],
+            };
+            handle(deps, mock_env(OWNER, &[]), proposal)
+        };
+
+        let pay = vec![CosmosMsg::Bank(BankMsg::Send {
+            from_address: MOCK_CONTRACT_ADDR.into(),
             to_address: SOMEBODY.into(),
             amount: vec![coin(1, "BTC")],
-        };
-        let msgs = vec![CosmosMsg::Bank(bank_msg)];
-        let proposal = HandleMsg::Propose {
+        })];
+
+        // one open and one passed proposal before the pause
+        let res = propose(&mut deps, pay.clone()).unwrap();
+        let open_id: u64 = res.log[2].value.parse().unwrap();
+        let res = propose(&mut deps, pay.clone()).unwrap();
+        let passed_id: u64 = res.log[2].value.parse().unwrap();
+        let yes_vote = HandleMsg::Vote { proposal_id: passed_id, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER1, &[]), yes_vote).unwrap();
+
+        let err = handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Pause { until: None }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotGuardian {}.to_string()));
+        let expired = HandleMsg::Pause { until: Some(Expiration::AtHeight(1)) };
+        let err = handle(&mut deps, mock_env(GUARDIAN, &[]), expired).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::PauseExpired {}.to_string()));
+        handle(&mut deps, mock_env(GUARDIAN, &[]), HandleMsg::Pause { until: None }).unwrap();
+
+        let res: ConfigResponse = from_binary(&query(&deps, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.pause, Some(Pause { guardian: GUARDIAN.to_string(), until: Expiration::Never {} }));
+
+        // everything is blocked
+        let err = propose(&mut deps, pay.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ProposePaused {}.to_string()));
+        let yes_vote = HandleMsg::Vote { proposal_id: open_id, vote: Vote::Yes };
+        let err = handle(&mut deps, mock_env(VOTER1, &[]), yes_vote).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::VotePaused {}.to_string()));
+        let execute = HandleMsg::Execute { proposal_id: passed_id, step: None };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), execute.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ExecutePaused {}.to_string()));
+
+        // except for proposals that unpause, the only way to lift it early
+        let err = handle(&mut deps, mock_env(OWNER, &[]), HandleMsg::Unpause {}).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        let unpause = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            callback_code_hash: "multisig_hash".to_string(),
+            msg: to_binary(&HandleMsg::Unpause {}).unwrap(),
+            send: vec![],
+        });
+        let res = propose(&mut deps, vec![unpause.clone()]).unwrap();
+        let unpause_id: u64 = res.log[2].value.parse().unwrap();
+        let yes_vote = HandleMsg::Vote { proposal_id: unpause_id, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER1, &[]), yes_vote).unwrap();
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id: unpause_id, step: None }).unwrap();
+        assert_eq!(res.messages, vec![unpause]);
+        handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), HandleMsg::Unpause {}).unwrap();
+
+        handle(&mut deps, mock_env(SOMEBODY, &[]), execute).unwrap();
+        let err = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), HandleMsg::Unpause {}).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotPaused {}.to_string()));
+
+        // the overruled guardian can't pause again straight away
+        let err = handle(&mut deps, mock_env(GUARDIAN, &[]), HandleMsg::Pause { until: None }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::GuardianOverruled {}.to_string()));
+
+        // until the members re-appoint it
+        let update = HandleMsg::UpdateGuardians { guardians: vec![GUARDIAN.to_string()] };
+        let err = handle(&mut deps, mock_env(OWNER, &[]), update.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), update).unwrap();
+
+        // a timed pause lifts by itself
+        let env = mock_env(GUARDIAN, &[]);
+        let until = Expiration::AtHeight(env.block.height + 10);
+        handle(&mut deps, env.clone(), HandleMsg::Pause { until: Some(until) }).unwrap();
+        let err = propose(&mut deps, pay.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ProposePaused {}.to_string()));
+        let mut later = mock_env(OWNER, &[]);
+        later.block.height += 10;
+        let proposal = HandleMsg::ProposeAction {
             title: "Pay somebody".to_string(),
             description: "Do I pay her?".to_string(),
-            msgs,
+            action: ProposalAction::Signal {},
             latest: None,
+            execute_at: None,
         };
-        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+        handle(&mut deps, later, proposal).unwrap();
+    }
 
-        // Get the proposal id from the logs
-        let proposal_id: u64 = res.log[2].value.parse().unwrap();
 
-        // Owner cannot vote (again)
-        let yes_vote = HandleMsg::Vote {
-            proposal_id,
-            vote: Vote::Yes,
+    #[test]
+    fn test_export_import_state() {
+        let mut old = mock_dependencies(20,&[]);
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(VOTER2, 1), voter(NOWEIGHT_VOTER, 0)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        let err = handle(&mut deps, mock_env(OWNER, &[]), yes_vote.clone()).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::AlreadyVoted {}.to_string()));
+        init(&mut old, mock_env(OWNER, &[]), init_msg.clone()).unwrap();
 
-        // Only voters can vote
-        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), yes_vote.clone()).unwrap_err();
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        for _ in 0..3 {
+            handle(&mut old, mock_env(OWNER, &[]), proposal.clone()).unwrap();
+        }
+        let yes_vote = HandleMsg::Vote { proposal_id: 2, vote: Vote::Yes };
+        handle(&mut old, mock_env(VOTER1, &[]), yes_vote).unwrap();
+
+        // export in pages of two proposals
+        let export = |start_after: Option<u64>| -> StateSnapshot {
+            let msg = QueryMsg::ExportState { start_after, limit: Some(2) };
+            from_binary(&query(&old, msg).unwrap()).unwrap()
+        };
+        let first = export(None);
+        assert_eq!(first.version, SNAPSHOT_VERSION);
+        assert_eq!(first.proposal_count, 3);
+        assert_eq!(first.voters.len(), 4);
+        assert_eq!(first.proposals.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(first.proposals[1].ballots.len(), 2);
+        let second = export(Some(2));
+        assert_eq!(second.proposals.iter().map(|p| p.id).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(export(Some(3)).proposals, vec![]);
+
+        // a contract not initialized for import refuses snapshots
+        let mut new = mock_dependencies(20,&[]);
+        init(&mut new, mock_env(OWNER, &[]), init_msg.clone()).unwrap();
+        let import = HandleMsg::ImportState { snapshot: Box::new(first.clone()) };
+        let err = handle(&mut new, mock_env(OWNER, &[]), import.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ImportClosed {}.to_string()));
+
+        let mut new = mock_dependencies(20,&[]);
+        let init_msg = InitMsg { import_state: Some(true), ..init_msg };
+        init(&mut new, mock_env(OWNER, &[]), init_msg).unwrap();
+
+        // only initial voters with voting power import, and only snapshots they can read
+        let err = handle(&mut new, mock_env(SOMEBODY, &[]), import.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        let err = handle(&mut new, mock_env(NOWEIGHT_VOTER, &[]), import.clone()).unwrap_err();
         assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        let future = StateSnapshot { version: SNAPSHOT_VERSION + 1, ..first.clone() };
+        let err = handle(&mut new, mock_env(OWNER, &[]), HandleMsg::ImportState { snapshot: Box::new(future) }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::UnsupportedSnapshot {}.to_string()));
+
+        // from a multisig with the same members
+        let other_voters = StateSnapshot { voters: first.voters[..3].to_vec(), ..first.clone() };
+        let err = handle(&mut new, mock_env(OWNER, &[]), HandleMsg::ImportState { snapshot: Box::new(other_voters) }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::SnapshotMismatch {}.to_string()));
+        let mut other_weight = first.clone();
+        other_weight.config.total_weight += 1;
+        let err = handle(&mut new, mock_env(OWNER, &[]), HandleMsg::ImportState { snapshot: Box::new(other_weight) }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::SnapshotMismatch {}.to_string()));
+        let mut stranger_ballot = first.clone();
+        stranger_ballot.proposals[1].ballots[0].voter = SOMEBODY.to_string();
+        let err = handle(&mut new, mock_env(OWNER, &[]), HandleMsg::ImportState { snapshot: Box::new(stranger_ballot) }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::SnapshotUnknownVoter {}.to_string()));
+
+        // no new proposals while importing
+        let err = handle(&mut new, mock_env(OWNER, &[]), proposal.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ImportInProgress {}.to_string()));
+
+        handle(&mut new, mock_env(OWNER, &[]), import.clone()).unwrap();
+        let err = handle(&mut new, mock_env(VOTER1, &[]), import).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::SnapshotOutOfOrder {}.to_string()));
+        handle(&mut new, mock_env(VOTER1, &[]), HandleMsg::ImportState { snapshot: Box::new(second) }).unwrap();
+
+        // every initial voter with voting power must approve the import, again after each page
+        let res = handle(&mut new, mock_env(OWNER, &[]), HandleMsg::FinishImport {}).unwrap();
+        assert_eq!(res.log[2], log("finished", false));
+        handle(&mut new, mock_env(VOTER1, &[]), HandleMsg::FinishImport {}).unwrap();
+        let last = HandleMsg::ImportState { snapshot: Box::new(export(Some(3))) };
+        handle(&mut new, mock_env(VOTER2, &[]), last).unwrap();
+        let err = handle(&mut new, mock_env(NOWEIGHT_VOTER, &[]), HandleMsg::FinishImport {}).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
+        for approver in &[VOTER2, OWNER] {
+            let res = handle(&mut new, mock_env(*approver, &[]), HandleMsg::FinishImport {}).unwrap();
+            assert_eq!(res.log[2], log("finished", false));
+        }
+        let res = handle(&mut new, mock_env(VOTER1, &[]), HandleMsg::FinishImport {}).unwrap();
+        assert_eq!(res.log[2], log("finished", true));
+        let err = handle(&mut new, mock_env(VOTER2, &[]), HandleMsg::FinishImport {}).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ImportClosed {}.to_string()));
+
+        // proposals and ballots read the same as in the old contract
+        for proposal_id in 1..=3 {
+            let msg = QueryMsg::Proposal { proposal_id };
+            assert_eq!(query(&new, msg.clone()).unwrap(), query(&old, msg).unwrap());
+        }
+        let msg = QueryMsg::Vote { proposal_id: 2, voter: VOTER1.to_string() };
+        assert_eq!(query(&new, msg.clone()).unwrap(), query(&old, msg).unwrap());
+
+        // imported proposals are read-only history
+        let no_vote = HandleMsg::Vote { proposal_id: 1, vote: Vote::No };
+        let err = handle(&mut new, mock_env(VOTER2, &[]), no_vote).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ImportedProposal {}.to_string()));
+        let execute = HandleMsg::Execute { proposal_id: 2, step: None };
+        let err = handle(&mut new, mock_env(OWNER, &[]), execute).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ImportedProposal {}.to_string()));
+        let mut expired = mock_env(SOMEBODY, &[]);
+        expired.block.time += 2000001;
+        let err = handle(&mut new, expired, HandleMsg::Close { proposal_id: 1 }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ImportedProposal {}.to_string()));
+
+        // new proposals continue the numbering
+        let res = handle(&mut new, mock_env(OWNER, &[]), proposal).unwrap();
+        assert_eq!(res.log[2].value, "4");
+    }
 
-        // But voter1 can
-        let res = handle(&mut deps, mock_env(VOTER1, &[]), yes_vote.clone()).unwrap();
 
-        // Verify
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER1),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Open")],
-                data: None
-            }
+    #[test]
+    fn test_v01_deployment_keeps_working() {
+        use cosmwasm_storage::{to_length_prefixed, to_length_prefixed_nested};
+
+        // raw storage of a v0.1 multisig with one open proposal
+        let mut deps = mock_dependencies(6,&[]);
+        let mut set = |key: Vec<u8>, value: &str| deps.storage.set(&key, value.trim_end().as_bytes());
+        set(to_length_prefixed(b"config"), include_str!("../testdata/v0.1/config.json"));
+        set(to_length_prefixed(b"proposal_count"), "1");
+        set(to_length_prefixed(b"list_of_voters"), include_str!("../testdata/v0.1/voters_list.json"));
+        for (addr, weight) in &[("owner", "1"), ("voter1", "2"), ("voter2", "3")] {
+            set([to_length_prefixed(b"voters"), addr.as_bytes().to_vec()].concat(), weight);
+        }
+        set(
+            [to_length_prefixed(b"proposals"), 1u64.to_le_bytes().to_vec()].concat(),
+            include_str!("../testdata/v0.1/proposal.json"),
+        );
+        set(
+            [to_length_prefixed_nested(&[b"votes", &1u64.to_le_bytes()]), b"owner".to_vec()].concat(),
+            include_str!("../testdata/v0.1/ballot.json"),
         );
 
-        // No/Veto votes have no effect on the tally
-        // Get the proposal id from the logs
-        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        let res: ConfigResponse = from_binary(&query(&deps, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.threshold, Threshold::AbsoluteCount { weight: 4 });
+        assert_eq!(res.threshold_rules, vec![]);
+        assert_eq!(res.proposal_count, 1);
+
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.title, "Pay somebody");
+        assert_eq!(prop.status, Status::Open);
+        assert_eq!(prop.proposer, "");
+
+        // it is found without the open proposals index
+        let msg = QueryMsg::PendingForVoter { address: "voter2".to_string() };
+        let res: ProposalListResponse = from_binary(&query(&deps, msg).unwrap()).unwrap();
+        assert_eq!(res.proposals.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1]);
+
+        // the contract address is unknown until the first message records it
+        let simulate_msg = QueryMsg::SimulateExecute { proposal_id: 1 };
+        let err = query(&deps, simulate_msg.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::UnknownContractAddress {}.to_string()));
+
+        // the old proposal can still pass and execute
+        let env = mock_env("voter2", &[]);
+        let yes_vote = HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes };
+        let res = handle(&mut deps, env.clone(), yes_vote).unwrap();
+        assert_eq!(res.log[3].value, "Passed");
+        let simulated: SimulateExecuteResponse = from_binary(&query(&deps, simulate_msg).unwrap()).unwrap();
+        assert_eq!(simulated.msgs.len(), 1);
+        let res = handle(&mut deps, env, HandleMsg::Execute { proposal_id: 1, step: None }).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // and new proposals are made as usual
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        let res = handle(&mut deps, mock_env("voter1", &[]), proposal).unwrap();
+        assert_eq!(res.log[2].value, "2");
+        assert_eq!(open_proposals_read(&deps.storage).load().unwrap(), vec![2]);
+    }
 
-        // Compute the current tally
-        let tally = get_tally(&mut deps, proposal_id);
 
-        // Cast a No vote
-        let no_vote = HandleMsg::Vote {
-            proposal_id,
-            vote: Vote::No,
+    #[test]
+    fn test_prune_proposals() {
+        use sha2::{Digest, Sha256};
+
+        let mut deps = mock_dependencies(20,&[]);
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(VOTER2, 1)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        handle(&mut deps, mock_env(VOTER2, &[]), no_vote.clone()).unwrap();
+        init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
 
-        // Cast a Veto vote
-        let veto_vote = HandleMsg::Vote {
-            proposal_id,
-            vote: Vote::Veto,
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
         };
-        handle(&mut deps, mock_env(VOTER3, &[]), veto_vote).unwrap();
+        for _ in 0..3 {
+            handle(&mut deps, mock_env(OWNER, &[]), proposal.clone()).unwrap();
+        }
+        // 1 is executed, 2 rejected and 3 stays open
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes }).unwrap();
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Execute { proposal_id: 1, step: None }).unwrap();
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Vote { proposal_id: 2, vote: Vote::No }).unwrap();
+        handle(&mut deps, mock_env(VOTER2, &[]), HandleMsg::Vote { proposal_id: 2, vote: Vote::No }).unwrap();
+
+        let prune = HandleMsg::Prune { before_id: 10 };
+        let err = handle(&mut deps, mock_env(OWNER, &[]), prune.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::Unauthorized {}.to_string()));
 
-        // Verify
-        assert_eq!(tally, get_tally(&deps, proposal_id));
+        let res = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), prune.clone()).unwrap();
+        assert_eq!(res.log[1].value, "2");
+        // the next prune starts at the open proposal
+        assert_eq!(prune_start_read(&deps.storage).load().unwrap(), 3);
+
+        // pruned proposals are queried in summary form, without ballots
+        let summary: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(summary.status, Status::Executed);
+        assert_eq!(summary.title, "");
+        assert_eq!(summary.msgs, vec![]);
+        assert_eq!(summary.proposer, OWNER);
+        let archived = summary.archived.unwrap();
+        assert_eq!(archived.title_hash, Binary(Sha256::digest(b"Pay somebody").to_vec()));
+        assert_eq!(archived.votes.yes, 2);
+        let msg = QueryMsg::Vote { proposal_id: 1, voter: VOTER1.to_string() };
+        let vote: VoteResponse = from_binary(&query(&deps, msg).unwrap()).unwrap();
+        assert_eq!(vote.vote, None);
+
+        let list: ProposalListResponse =
+            from_binary(&query(&deps, QueryMsg::ListProposals { start_after: None, limit: None }).unwrap()).unwrap();
+        let statuses: Vec<_> = list.proposals.iter().map(|p| p.status).collect();
+        assert_eq!(statuses, vec![Status::Executed, Status::Rejected, Status::Open]);
+        assert_eq!(list.proposals[1].archived.as_ref().unwrap().votes.no, 2);
+        assert_eq!(list.proposals[2].title, "Pay somebody");
+        assert_eq!(list.proposals[2].archived, None);
+
+        // nothing left to prune
+        let res = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), prune.clone()).unwrap();
+        assert_eq!(res.log[1].value, "0");
+        assert_eq!(prune_start_read(&deps.storage).load().unwrap(), 3);
+
+        // until the open proposal is executed
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Vote { proposal_id: 3, vote: Vote::Yes }).unwrap();
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Execute { proposal_id: 3, step: None }).unwrap();
+        let res = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), prune).unwrap();
+        assert_eq!(res.log[1].value, "1");
+        assert_eq!(prune_start_read(&deps.storage).load().unwrap(), 4);
+    }
 
-        // Once voted, votes cannot be changed
-        let err = handle(&mut deps, mock_env(VOTER3, &[]), yes_vote.clone()).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::AlreadyVoted {}.to_string()));
-        assert_eq!(tally, get_tally(&deps, proposal_id));
 
-        // Expired proposals cannot be voted
-        let env = match voting_period {
-            Duration::Time(duration) => mock_env_time(duration + 1),
-            Duration::Height(duration) => mock_env_height(duration + 1),
+    #[test]
+    fn test_export_import_pruned_proposals() {
+        let mut old = mock_dependencies(20,&[]);
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(VOTER2, 1)],
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        let err = handle(&mut deps, env, no_vote).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::Expired {}.to_string()));
-
-        // Vote it again, so it passes
-        let res = handle(&mut deps, mock_env(VOTER4, &[]), yes_vote.clone()).unwrap();
+        init(&mut old, mock_env(OWNER, &[]), init_msg.clone()).unwrap();
 
-        // Verify
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER4),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Passed")],
-                data: None
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        for _ in 0..3 {
+            handle(&mut old, mock_env(OWNER, &[]), proposal.clone()).unwrap();
+        }
+        // 1 is executed and pruned
+        handle(&mut old, mock_env(VOTER1, &[]), HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes }).unwrap();
+        handle(&mut old, mock_env(VOTER1, &[]), HandleMsg::Execute { proposal_id: 1, step: None }).unwrap();
+        handle(&mut old, mock_env(MOCK_CONTRACT_ADDR, &[]), HandleMsg::Prune { before_id: 2 }).unwrap();
+
+        // it is exported as its summary
+        let msg = QueryMsg::ExportState { start_after: None, limit: None };
+        let snapshot: StateSnapshot = from_binary(&query(&old, msg).unwrap()).unwrap();
+        assert_eq!(snapshot.proposals.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(snapshot.proposals[0].proposal, None);
+        assert_eq!(snapshot.proposals[0].archived.as_ref().unwrap().status, Status::Executed);
+        assert_eq!(snapshot.proposals[1].archived, None);
+
+        let import = |snapshot: StateSnapshot| {
+            let mut new = mock_dependencies(20,&[]);
+            let init_msg = InitMsg { import_state: Some(true), ..init_msg.clone() };
+            init(&mut new, mock_env(OWNER, &[]), init_msg).unwrap();
+            handle(&mut new, mock_env(OWNER, &[]), HandleMsg::ImportState { snapshot: Box::new(snapshot) }).unwrap();
+            for approver in &[OWNER, VOTER1, VOTER2] {
+                handle(&mut new, mock_env(*approver, &[]), HandleMsg::FinishImport {}).unwrap();
             }
-        );
+            new
+        };
+        let list = |deps: &Extern<_, _, _>| -> (Vec<u64>, Binary, Binary) {
+            let msg = QueryMsg::ListProposals { start_after: None, limit: None };
+            let listed = query(deps, msg).unwrap();
+            let ids = from_binary::<ProposalListResponse>(&listed).unwrap().proposals.iter().map(|p| p.id).collect();
+            let msg = QueryMsg::ReverseProposals { start_before: None, limit: None };
+            (ids, listed, query(deps, msg).unwrap())
+        };
 
-        // non-Open proposals cannot be voted
-        let err = handle(&mut deps, mock_env(VOTER5, &[]), yes_vote).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::NotOpen {}.to_string()));
+        // the new contract lists the same proposals, summary included
+        let new = import(snapshot.clone());
+        assert_eq!(list(&new), list(&old));
+        let msg = QueryMsg::Proposal { proposal_id: 1 };
+        assert_eq!(query(&new, msg.clone()).unwrap(), query(&old, msg).unwrap());
+
+        // proposals missing from a snapshot are skipped by the listings
+        let mut gap = snapshot;
+        gap.proposals.remove(1);
+        let new = import(gap);
+        let (ids, _, reversed) = list(&new);
+        assert_eq!(ids, vec![1, 3]);
+        let reversed: ProposalListResponse = from_binary(&reversed).unwrap();
+        assert_eq!(reversed.proposals.iter().map(|p| p.id).collect::<Vec<_>>(), vec![3, 1]);
+        assert!(query(&new, QueryMsg::Proposal { proposal_id: 2 }).is_err());
+    }
 
-        // Propose
-        let bank_msg = BankMsg::Send {
-            from_address: OWNER.into(),
-            to_address: SOMEBODY.into(),
-            amount: vec![coin(1, "BTC")],
+
+    #[test]
+    fn test_address_validation() {
+        let mut deps = mock_dependencies(20,&[]);
+        let init_msg = |voters: Vec<Voter>| InitMsg {
+            voters,
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+
+        // a duplicate would inflate the total weight
+        let msg = init_msg(vec![voter(OWNER, 1), voter(VOTER1, 1), voter(OWNER, 2)]);
+        let err = init(&mut deps, mock_env(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DuplicateVoter {}.to_string()));
+
+        // invalid addresses are rejected rather than becoming phantom members
+        let msg = init_msg(vec![voter(OWNER, 1), voter("x", 1)]);
+        let err = init(&mut deps, mock_env(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Invalid input: human address too short"));
+        let msg = InitMsg { guardians: Some(vec!["x".to_string()]), ..init_msg(vec![voter(OWNER, 1)]) };
+        init(&mut deps, mock_env(OWNER, &[]), msg).unwrap_err();
+
+        init(&mut deps, mock_env(OWNER, &[]), init_msg(vec![voter(OWNER, 1), voter(VOTER1, 2)])).unwrap();
+
+        // voters and ballots are keyed by canonical address
+        let canonical = deps.api.canonical_address(&HumanAddr::from(VOTER1)).unwrap();
+        assert_eq!(voters_read(&deps.storage).load(canonical.as_slice()).unwrap(), 2);
+        assert_eq!(voters_read(&deps.storage).may_load(VOTER1.as_bytes()).unwrap(), None);
+
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay somebody".to_string(),
+            description: "Do I pay her?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        handle(&mut deps, mock_env(VOTER1, &[]), proposal).unwrap();
+        let ballot = ballots_read(&deps.storage, 1).load(canonical.as_slice()).unwrap();
+        assert_eq!(ballot.weight, 2);
+
+        // and reported with their human address
+        let msg = QueryMsg::Vote { proposal_id: 1, voter: VOTER1.to_string() };
+        let res: VoteResponse = from_binary(&query(&deps, msg).unwrap()).unwrap();
+        assert_eq!(res.vote.unwrap().voter, VOTER1);
+        let msg = QueryMsg::Vote { proposal_id: 1, voter: "x".to_string() };
+        query(&deps, msg).unwrap_err();
+        let msg = QueryMsg::Voter { address: "x".to_string() };
+        query(&deps, msg).unwrap_err();
+    }
+
+
+    #[test]
+    fn test_simulate_execute() {
+        let mut deps = mock_dependencies(20, &[coin(150, "uscrt")]);
+        let token = DepositToken::Native { denom: "uscrt".to_string() };
+        setup_deposit_case(&mut deps, token, DepositRefundPolicy::OnlyPassed);
+
+        let send = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(40, "uscrt")],
+        });
+        let call = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("token"),
+            callback_code_hash: "hash".to_string(),
+            msg: Binary::from(br#"{"burn":{}}"#.to_vec()),
+            send: vec![coin(10, "uscrt")],
+        });
         let proposal = HandleMsg::Propose {
             title: "Pay somebody".to_string(),
             description: "Do I pay her?".to_string(),
-            msgs,
+            msgs: vec![send.clone(), call.clone()],
             latest: None,
+            execute_at: None,
+            depends_on: vec![],
         };
-        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
-
-        // Get the proposal id from the logs
-        let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal).unwrap();
+
+        // an open proposal is simulated as if it passed, refunding the deposit
+        let msg = QueryMsg::SimulateExecute { proposal_id: 1 };
+        let res: SimulateExecuteResponse = from_binary(&query(&deps, msg.clone()).unwrap()).unwrap();
+        let refund = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(OWNER),
+            amount: vec![coin(100, "uscrt")],
+        });
+        assert_eq!(res.msgs, vec![send, call, refund]);
+        assert_eq!(res.bank_sends.len(), 2);
+        assert_eq!(res.bank_sends[0].to_address, HumanAddr::from(SOMEBODY));
+        assert_eq!(res.bank_sends[0].amount, vec![coin(40, "uscrt")]);
+        assert_eq!(res.contract_calls.len(), 1);
+        assert_eq!(res.contract_calls[0].msg, r#"{"burn":{}}"#);
+        assert_eq!(res.total_sent, vec![coin(150, "uscrt")]);
+        assert_eq!(res.balance, vec![coin(150, "uscrt")]);
+        assert!(res.balance_covers);
+
+        // the preview matches what execution sends
+        let vote = HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER1, &[]), vote).unwrap();
+        let simulated: SimulateExecuteResponse = from_binary(&query(&deps, msg.clone()).unwrap()).unwrap();
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id: 1, step: None }).unwrap();
+        assert_eq!(res.messages, simulated.msgs);
+        let err = query(&deps, msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
 
-        // Cast a No vote
-        let no_vote = HandleMsg::Vote {
-            proposal_id,
-            vote: Vote::No,
+        // sends beyond the balance are flagged
+        let proposal = HandleMsg::Propose {
+            title: "Pay everybody".to_string(),
+            description: "".to_string(),
+            msgs: vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                to_address: HumanAddr::from(SOMEBODY),
+                amount: vec![coin(100, "uscrt"), coin(1, "ueth")],
+            })],
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
         };
-        // Voter1 vote no, weight 1
-        let res = handle(&mut deps, mock_env(VOTER1, &[]), no_vote.clone()).unwrap();
+        handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal).unwrap();
+        let res: SimulateExecuteResponse = from_binary(
+            &query(&deps, QueryMsg::SimulateExecute { proposal_id: 2 }).unwrap()
+        ).unwrap();
+        assert_eq!(res.total_sent, vec![coin(200, "uscrt"), coin(1, "ueth")]);
+        assert!(!res.balance_covers);
+
+        // totals that don't fit in a u128 fail instead of wrapping
+        let drain = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(u128::MAX, "uscrt")],
+        });
+        let proposal = HandleMsg::Propose {
+            title: "Pay too much".to_string(),
+            description: "".to_string(),
+            msgs: vec![drain.clone(), drain],
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal).unwrap();
+        let err = query(&deps, QueryMsg::SimulateExecute { proposal_id: 3 }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::AmountOverflow {}.to_string()));
+    }
 
-        // Verify it is not enough to reject yet
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER1),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Open")],
-                data: None
-            }
-        );
 
-        // Voter 4 votes no, weight 4, total weight for no so far 5, need 14 to reject
-        let res = handle(&mut deps, mock_env(VOTER4, &[]), no_vote.clone()).unwrap();
+    #[test]
+    fn test_stepwise_execution() {
+        let mut deps = mock_dependencies(20, &[]);
+        let token = DepositToken::Native { denom: "uscrt".to_string() };
+        setup_deposit_case(&mut deps, token, DepositRefundPolicy::OnlyPassed);
+
+        let pay = |to: &str| CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(to),
+            amount: vec![coin(1, "uscrt")],
+        });
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay both".to_string(),
+            description: "One at a time".to_string(),
+            action: ProposalAction::Steps { msgs: vec![pay(SOMEBODY), pay(VOTER3)] },
+            latest: None,
+            execute_at: None,
+        };
+        handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal).unwrap();
+        let vote = HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER1, &[]), vote).unwrap();
+
+        // steps are executed on their own, in any order
+        let execute = |step: Option<u32>| HandleMsg::Execute { proposal_id: 1, step };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), execute(Some(2))).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidStep { step: 2 }.to_string()));
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), execute(Some(1))).unwrap();
+        assert_eq!(res.messages, vec![pay(VOTER3)]);
+        assert_eq!(res.log[3], log("step", "1"));
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), execute(Some(1))).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::StepAlreadyExecuted { step: 1 }.to_string()));
+
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.status, Status::PartiallyExecuted);
+        assert_eq!(prop.steps, vec![StepStatus::Pending, StepStatus::Executed]);
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Close { proposal_id: 1 }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotExpired {}.to_string()));
 
-        // Verify it is still open as we actually need no votes > 16 - 3
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER4),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Open")],
-                data: None
-            }
-        );
+        // the last step completes the proposal and refunds the deposit
+        let refund = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(OWNER),
+            amount: vec![coin(100, "uscrt")],
+        });
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), execute(None)).unwrap();
+        assert_eq!(res.messages, vec![pay(SOMEBODY), refund]);
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.status, Status::Executed);
+        assert_eq!(prop.steps, vec![StepStatus::Executed, StepStatus::Executed]);
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), execute(Some(0))).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
 
-        // Voter 3 votes no, weight 3, total weight for no far 8, need 14
-        let _res = handle(&mut deps, mock_env(VOTER3, &[]), no_vote.clone()).unwrap();
+        // other proposals are executed as a whole
+        let proposal = HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "".to_string(),
+            msgs: vec![pay(SOMEBODY)],
+            latest: None,
+            execute_at: None,
+            depends_on: vec![],
+        };
+        handle(&mut deps, mock_env(VOTER2, &[coin(100, "uscrt")]), proposal).unwrap();
+        let err = handle(
+            &mut deps,
+            mock_env(SOMEBODY, &[]),
+            HandleMsg::Execute { proposal_id: 2, step: Some(0) },
+        ).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotStepwise {}.to_string()));
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 2 }).unwrap()).unwrap();
+        assert_eq!(prop.steps, vec![]);
+    }
 
-        // Voter 5 votes no, weight 5, total weight for no far 13, need 14
-        let res = handle(&mut deps, mock_env(VOTER5, &[]), no_vote.clone()).unwrap();
 
-        // Verify it is still open as we actually need no votes > 16 - 3
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER5),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Open")],
-                data: None
-            }
-        );
+    #[test]
+    fn test_abandon_failing_step() {
+        let mut deps = mock_dependencies(20, &[]);
+        let token = DepositToken::Native { denom: "uscrt".to_string() };
+        setup_deposit_case(&mut deps, token, DepositRefundPolicy::OnlyPassed);
+
+        // the second step always fails on chain
+        let pay = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(1, "uscrt")],
+        });
+        let broken = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("broken"),
+            callback_code_hash: "broken_hash".to_string(),
+            msg: Binary::from(br#"{"fail":{}}"#.to_vec()),
+            send: vec![],
+        });
+        let proposal = HandleMsg::ProposeAction {
+            title: "Pay and call".to_string(),
+            description: "One at a time".to_string(),
+            action: ProposalAction::Steps { msgs: vec![pay.clone(), broken] },
+            latest: None,
+            execute_at: None,
+        };
+        handle(&mut deps, mock_env(OWNER, &[coin(100, "uscrt")]), proposal).unwrap();
+        let vote = HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes };
+        handle(&mut deps, mock_env(VOTER1, &[]), vote).unwrap();
+        handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id: 1, step: Some(0) }).unwrap();
+
+        let dependent = HandleMsg::Propose {
+            title: "After the call".to_string(),
+            description: "".to_string(),
+            msgs: vec![pay],
+            latest: None,
+            execute_at: None,
+            depends_on: vec![1],
+        };
+        handle(&mut deps, mock_env(VOTER2, &[coin(100, "uscrt")]), dependent).unwrap();
 
-        // Voter 2 votes no, weight 2, total weight for no so far 15, need 14.
-        // Can now reject
-        let res = handle(&mut deps, mock_env(VOTER2, &[]), no_vote).unwrap();
+        // once expired, the remaining steps are abandoned and the deposit settled as failed
+        let close = HandleMsg::Close { proposal_id: 1 };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), close.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotExpired {}.to_string()));
+        let mut env = mock_env(SOMEBODY, &[]);
+        env.block.time += 2000000;
+        let res = handle(&mut deps, env, close).unwrap();
+        assert!(res.messages.is_empty());
+
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.status, Status::Rejected);
+        assert_eq!(prop.steps, vec![StepStatus::Executed, StepStatus::Pending]);
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), HandleMsg::Execute { proposal_id: 1, step: Some(1) }).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
 
-        // Verify it is rejected as, 15 no votes > 16 - 3
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER2),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Rejected")],
-                data: None
-            }
-        );
+        // its dependents can no longer run either
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 2 }).unwrap()).unwrap();
+        assert_eq!(prop.status, Status::Rejected);
+        for proposal_id in 1..=2 {
+            let deposit: DepositResponse =
+                from_binary(&query(&deps, QueryMsg::Deposit { proposal_id }).unwrap()).unwrap();
+            assert_eq!(deposit.deposit, None);
+        }
+
+        // and it can be pruned
+        let res = handle(&mut deps, mock_env(MOCK_CONTRACT_ADDR, &[]), HandleMsg::Prune { before_id: 2 }).unwrap();
+        assert_eq!(res.log[1], log("pruned", 1));
     }
 
+
     #[test]
-    fn test_execute_works() {
-        let mut deps = mock_dependencies(6,&[]);
+    fn test_scheduled_execution() {
+        let mut deps = mock_dependencies(20, &[]);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, Threshold::AbsoluteCount { weight: 3 }, Duration::Time(2000000)).unwrap();
 
-        let threshold = Threshold::AbsoluteCount { weight: 3 };
-        let voting_period = Duration::Time(2000000);
+        let env = mock_env(SOMEBODY, &[]);
+        let pay = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(1, "BTC")],
+        });
+        let proposal = |execute_at: Expiration| HandleMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "On the first of the month".to_string(),
+            msgs: vec![pay.clone()],
+            latest: None,
+            execute_at: Some(execute_at),
+            depends_on: vec![],
+        };
+        // the first two pass right away, the third is still open
+        handle(&mut deps, mock_env(VOTER3, &[]), proposal(Expiration::AtTime(env.block.time + 1000))).unwrap();
+        handle(&mut deps, mock_env(VOTER3, &[]), proposal(Expiration::AtHeight(env.block.height + 10))).unwrap();
+        handle(&mut deps, mock_env(OWNER, &[]), proposal(Expiration::AtHeight(env.block.height + 5))).unwrap();
+        let err = handle(&mut deps, mock_env(VOTER3, &[]), proposal(Expiration::Never {})).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidExecuteAt {}.to_string()));
+
+        let queue: Vec<u64> = due_queue_read(&deps.storage).load().unwrap().iter().map(|due| due.proposal_id).collect();
+        assert_eq!(queue, vec![3, 2, 1]);
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.execute_at, Some(Expiration::AtTime(env.block.time + 1000)));
+
+        // nothing runs early
+        let execute = HandleMsg::Execute { proposal_id: 1, step: None };
+        let err = handle(&mut deps, env.clone(), execute).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NotDue {}.to_string()));
+        let res = handle(&mut deps, env.clone(), HandleMsg::ExecuteDue { limit: None, start_after: None }).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.log[2], log("executed", ""));
+
+        // due proposals run once passed, open ones wait
+        let mut later = env.clone();
+        later.block.height += 10;
+        later.block.time += 1000;
+        let res = handle(&mut deps, later.clone(), HandleMsg::ExecuteDue { limit: Some(1), start_after: None }).unwrap();
+        assert_eq!(res.messages, vec![pay.clone()]);
+        assert_eq!(res.log[2], log("executed", "2"));
+        let res = handle(&mut deps, later.clone(), HandleMsg::ExecuteDue { limit: None, start_after: None }).unwrap();
+        assert_eq!(res.messages, vec![pay]);
+        assert_eq!(res.log[2], log("executed", "1"));
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.status, Status::Executed);
+        let queue: Vec<u64> = due_queue_read(&deps.storage).load().unwrap().iter().map(|due| due.proposal_id).collect();
+        assert_eq!(queue, vec![3]);
+
+        // closing leaves the queue
+        later.block.time += 2000000;
+        handle(&mut deps, later, HandleMsg::Close { proposal_id: 3 }).unwrap();
+        assert!(due_queue_read(&deps.storage).load().unwrap().is_empty());
+    }
 
-        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
-        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
 
-        // Propose
-        let bank_msg = BankMsg::Send {
-            from_address: OWNER.into(),
-            to_address: SOMEBODY.into(),
+    #[test]
+    fn test_failing_due_proposal_is_skipped() {
+        let mut deps = mock_dependencies(20, &[]);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, Threshold::AbsoluteCount { weight: 3 }, Duration::Time(2000000)).unwrap();
+
+        let env = mock_env(SOMEBODY, &[]);
+        let execute_at = Expiration::AtHeight(env.block.height + 5);
+        let broken = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("broken"),
+            callback_code_hash: "broken_hash".to_string(),
+            msg: Binary::from(br#"{"fail":{}}"#.to_vec()),
+            send: vec![],
+        });
+        let pay = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
             amount: vec![coin(1, "BTC")],
+        });
+
+        // action proposals can be scheduled too
+        let failing = HandleMsg::ProposeAction {
+            title: "Call broken".to_string(),
+            description: "Always fails".to_string(),
+            action: ProposalAction::Steps { msgs: vec![broken.clone()] },
+            latest: None,
+            execute_at: Some(execute_at),
         };
-        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+        handle(&mut deps, mock_env(VOTER3, &[]), failing).unwrap();
+        let prop: ProposalResponse =
+            from_binary(&query(&deps, QueryMsg::Proposal { proposal_id: 1 }).unwrap()).unwrap();
+        assert_eq!(prop.execute_at, Some(execute_at));
         let proposal = HandleMsg::Propose {
             title: "Pay somebody".to_string(),
-            description: "Do I pay her?".to_string(),
-            msgs: msgs.clone(),
+            description: "".to_string(),
+            msgs: vec![pay.clone()],
             latest: None,
+            execute_at: Some(execute_at),
+            depends_on: vec![],
         };
-        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+        handle(&mut deps, mock_env(VOTER3, &[]), proposal).unwrap();
+
+        // the failing proposal heads the queue, and would revert every call starting there,
+        // so the next ones are executed after it
+        let mut later = env;
+        later.block.height += 5;
+        let skip = HandleMsg::ExecuteDue { limit: None, start_after: Some(1) };
+        let res = handle(&mut deps, later.clone(), skip).unwrap();
+        assert_eq!(res.messages, vec![pay]);
+        assert_eq!(res.log[2], log("executed", "2"));
+
+        let res = handle(&mut deps, later, HandleMsg::ExecuteDue { limit: None, start_after: None }).unwrap();
+        assert_eq!(res.messages, vec![broken]);
+        assert_eq!(res.log[2], log("executed", "1"));
+    }
 
-        // Get the proposal id from the logs
-        let proposal_id: u64 = res.log[2].value.parse().unwrap();
 
-        // Only Passed can be executed
-        let execution = HandleMsg::Execute { proposal_id };
-        let err = handle(&mut deps, mock_env(OWNER, &[]), execution.clone()).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::WrongExecuteStatus {}.to_string()));
+    #[test]
+    fn test_dependent_proposals() {
+        let mut deps = mock_dependencies(20, &[]);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, Threshold::AbsoluteCount { weight: 3 }, Duration::Time(2000000)).unwrap();
 
-        // Vote it, so it passes
-        let vote = HandleMsg::Vote {
-            proposal_id,
-            vote: Vote::Yes,
+        let pay = CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            to_address: HumanAddr::from(SOMEBODY),
+            amount: vec![coin(1, "BTC")],
+        });
+        let proposal = |depends_on: Vec<u64>| HandleMsg::Propose {
+            title: "Pay vendor".to_string(),
+            description: "After the upgrade".to_string(),
+            msgs: vec![pay.clone()],
+            latest: None,
+            execute_at: None,
+            depends_on,
+        };
+        let status = |deps: &Extern<_, _, _>, proposal_id: u64| -> ProposalResponse {
+            from_binary(&query(deps, QueryMsg::Proposal { proposal_id }).unwrap()).unwrap()
         };
-        let res = handle(&mut deps, mock_env(VOTER3, &[]), vote).unwrap();
 
-        // Verify
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","vote"),
-                    log("sender", VOTER3),
-                    log("proposal_id", proposal_id.to_string()),
-                    log("status", "Passed")],
-                data: None
-            }
-        );
+        // a dependent waits until its dependencies are executed
+        handle(&mut deps, mock_env(VOTER3, &[]), proposal(vec![])).unwrap();
+        handle(&mut deps, mock_env(VOTER3, &[]), proposal(vec![1, 1])).unwrap();
+        assert_eq!(status(&deps, 2).depends_on, vec![1]);
+        assert_eq!(status(&deps, 1).dependents, vec![2]);
+        let execute = |proposal_id: u64| HandleMsg::Execute { proposal_id, step: None };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), execute(2)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DependencyNotExecuted { id: 1 }.to_string()));
+        handle(&mut deps, mock_env(SOMEBODY, &[]), execute(1)).unwrap();
+        handle(&mut deps, mock_env(SOMEBODY, &[]), execute(2)).unwrap();
+
+        // dependencies must exist and cannot be the proposal itself
+        let err = handle(&mut deps, mock_env(VOTER3, &[]), proposal(vec![99])).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::UnknownDependency { id: 99 }.to_string()));
+        let err = handle(&mut deps, mock_env(VOTER3, &[]), proposal(vec![3])).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::SelfDependency {}.to_string()));
+
+        // rejecting a dependency rejects its dependents, directly or not
+        handle(&mut deps, mock_env(OWNER, &[]), proposal(vec![])).unwrap();
+        handle(&mut deps, mock_env(VOTER3, &[]), proposal(vec![3])).unwrap();
+        handle(&mut deps, mock_env(OWNER, &[]), proposal(vec![4])).unwrap();
+        assert_eq!(status(&deps, 4).status, Status::Passed);
+        let mut env = mock_env(SOMEBODY, &[]);
+        env.block.time += 2000000;
+        handle(&mut deps, env, HandleMsg::Close { proposal_id: 3 }).unwrap();
+        assert_eq!(status(&deps, 4).status, Status::Rejected);
+        assert_eq!(status(&deps, 5).status, Status::Rejected);
+        assert!(open_proposals_read(&deps.storage).load().unwrap().is_empty());
+        let err = handle(&mut deps, mock_env(VOTER3, &[]), proposal(vec![3])).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DependencyRejected { id: 3 }.to_string()));
+    }
 
-        // In passing: Try to close Passed fails
-        let closing = HandleMsg::Close { proposal_id };
-        let err = handle(&mut deps, mock_env(VOTER3, &[]), closing).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
 
-        // Execute works. Anybody can execute Passed proposals
-        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), execution).unwrap();
+    #[test]
+    fn test_vote_batch() {
+        let mut deps = mock_dependencies(20, &[]);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, Threshold::AbsoluteCount { weight: 5 }, Duration::Time(2000000)).unwrap();
 
-        // Verify
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: msgs,
-                log: vec![
-                    log("action","execute"),
-                    log("sender", SOMEBODY),
-                    log("proposal_id", proposal_id.to_string())],
-                data: None
-            }
-        );
+        let proposal = HandleMsg::ProposeAction {
+            title: "Routine".to_string(),
+            description: "Monthly approval".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        for _ in 0..2 {
+            handle(&mut deps, mock_env(OWNER, &[]), proposal.clone()).unwrap();
+        }
+        let msg: HandleMsg = from_slice(br#"{"vote_batch":{"votes":[[1,"yes"],[2,"no"]]}}"#).unwrap();
+        let expected = HandleMsg::VoteBatch { votes: vec![(1, Vote::Yes), (2, Vote::No)], mode: VoteBatchMode::AllOrNothing };
+        assert_eq!(msg, expected);
+        let batch = |mode: VoteBatchMode| HandleMsg::VoteBatch {
+            votes: vec![(1, Vote::Yes), (2, Vote::No), (99, Vote::Yes)],
+            mode,
+        };
 
-        // In passing: Try to close Executed fails
-        let closing = HandleMsg::Close { proposal_id };
-        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), closing).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
+        // by default one failing vote fails the batch
+        let msg = HandleMsg::VoteBatch { votes: vec![(99, Vote::Yes), (1, Vote::Yes)], mode: Default::default() };
+        handle(&mut deps, mock_env(VOTER4, &[]), msg).unwrap_err();
+        assert_eq!(get_tally(&deps, 1), 1);
+
+        // or it is skipped and reported
+        let res = handle(&mut deps, mock_env(VOTER4, &[]), batch(VoteBatchMode::BestEffort)).unwrap();
+        assert_eq!(res.log[2], log("voted", "2"));
+        assert_eq!(res.log[3], log("failed", "1"));
+        let data: VoteBatchResponse = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(data.results.len(), 3);
+        assert_eq!(data.results[0], VoteBatchResult { proposal_id: 1, status: Some(Status::Passed), error: None });
+        assert_eq!(data.results[1], VoteBatchResult { proposal_id: 2, status: Some(Status::Open), error: None });
+        assert_eq!(data.results[2].status, None);
+        assert!(data.results[2].error.is_some());
+        assert_eq!(get_tally(&deps, 1), 5);
+
+        // a failed vote leaves nothing behind
+        let res = handle(&mut deps, mock_env(VOTER4, &[]), batch(VoteBatchMode::BestEffort)).unwrap();
+        let data: VoteBatchResponse = from_binary(&res.data.unwrap()).unwrap();
+        let already_voted = StdError::generic_err(ContractError::AlreadyVoted {}.to_string()).to_string();
+        assert_eq!(data.results[1].error, Some(already_voted));
+        assert_eq!(get_tally(&deps, 1), 5);
+
+        let votes = vec![(1, Vote::Yes); MAX_BATCH_VOTES + 1];
+        let msg = HandleMsg::VoteBatch { votes, mode: VoteBatchMode::BestEffort };
+        let err = handle(&mut deps, mock_env(VOTER5, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::TooManyVotes { max: MAX_BATCH_VOTES }.to_string()));
     }
 
+
     #[test]
-    fn test_close_works() {
-        let mut deps = mock_dependencies(6,&[]);
+    fn test_signed_votes() {
+        let mut deps = mock_dependencies(20, &[]);
+        // a key whose address the mock api can represent
+        let mut secret = [0; 32];
+        secret[30..].copy_from_slice(&4182u16.to_be_bytes());
+        let key = SigningKey::from_slice(&secret).unwrap();
+        let pubkey = key.verifying_key().to_encoded_point(true).as_bytes().to_vec();
+        let cold = pubkey_address(&deps.api, &pubkey).unwrap();
+        let init_msg = InitMsg {
+            voters: vec![voter(OWNER, 1), voter(VOTER1, 1), voter(cold.as_str(), 2)],
+            threshold: Threshold::AbsoluteCount { weight: 4 },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
+        };
+        init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
+        let proposal = HandleMsg::ProposeAction {
+            title: "Poll".to_string(),
+            description: "Do we agree?".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
+        };
+        handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        let chain_id = mock_env(SOMEBODY, &[]).block.chain_id;
+        // MockApi accepts any signature, what they bind is tested in `signed`
+        let sign = |nonce: u64| {
+            let mut signed = SignedVote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+                nonce,
+                pubkey: Binary(pubkey.clone()),
+                signature: Binary(vec![]),
+            };
+            let contract = HumanAddr::from(MOCK_CONTRACT_ADDR);
+            let hash = SignDoc::new(&chain_id, &contract, &cold, &signed).unwrap().hash().unwrap();
+            let signature: Signature = key.sign_prehash(&hash).unwrap();
+            signed.signature = Binary(signature.to_bytes().to_vec());
+            signed
+        };
 
-        let threshold = Threshold::AbsoluteCount { weight: 3 };
-        let voting_period = Duration::Height(2000000);
+        // anyone can relay the member's vote
+        let msg = HandleMsg::SubmitSignedVotes { votes: vec![sign(1)] };
+        let res = handle(&mut deps, mock_env(SOMEBODY, &[]), msg.clone()).unwrap();
+        assert_eq!(res.log[2], log("voted", "1"));
+        let vote: VoteResponse = from_binary(
+            &query(&deps, QueryMsg::Vote { proposal_id: 1, voter: cold.to_string() }).unwrap()
+        ).unwrap();
+        assert_eq!(vote.vote.unwrap().weight, 2);
+        assert_eq!(get_tally(&deps, 1), 3);
+
+        // a signature is only accepted once, and a new one still counts as the member's vote
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::NonceUsed { nonce: 1 }.to_string()));
+        let msg = HandleMsg::SubmitSignedVotes { votes: vec![sign(2)] };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::AlreadyVoted {}.to_string()));
 
-        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
-        setup_test_case(&mut deps, info.clone(), threshold, voting_period).unwrap();
+        let mut invalid = sign(3);
+        invalid.pubkey = Binary(vec![2; 32]);
+        let msg = HandleMsg::SubmitSignedVotes { votes: vec![invalid] };
+        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::InvalidPubkey {}.to_string()));
+    }
 
-        // Propose
-        let bank_msg = BankMsg::Send {
-            from_address: OWNER.into(),
-            to_address: SOMEBODY.into(),
-            amount: vec![coin(1, "BTC")],
+
+    #[test]
+    fn test_weights_near_u64_max() {
+        let mut deps = mock_dependencies(20,&[]);
+        let init_msg = |voters: Vec<Voter>, weight: u64| InitMsg {
+            voters,
+            threshold: Threshold::AbsoluteCount { weight },
+            max_voting_period: Duration::Time(2000000),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: None,
         };
-        let msgs = vec![CosmosMsg::Bank(bank_msg)];
+
+        // the total weight must fit in a u64
+        let msg = init_msg(vec![voter(OWNER, u64::MAX), voter(VOTER1, 1)], 1);
+        let err = init(&mut deps, mock_env(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::WeightOverflow {}.to_string()));
+
+        let voters = vec![voter(OWNER, u64::MAX - 2), voter(VOTER1, 1), voter(VOTER2, 1)];
+        init(&mut deps, mock_env(OWNER, &[]), init_msg(voters, u64::MAX - 1)).unwrap();
+
         let proposal = HandleMsg::Propose {
             title: "Pay somebody".to_string(),
             description: "Do I pay her?".to_string(),
-            msgs: msgs.clone(),
+            msgs: vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: MOCK_CONTRACT_ADDR.into(),
+                to_address: SOMEBODY.into(),
+                amount: vec![coin(1, "BTC")],
+            })],
             latest: None,
+            execute_at: None,
+            depends_on: vec![],
         };
         let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
-
-        // Get the proposal id from the logs
         let proposal_id: u64 = res.log[2].value.parse().unwrap();
+        assert_eq!(res.log[3].value, "Open");
 
-        let closing = HandleMsg::Close { proposal_id };
+        let vote = HandleMsg::Vote { proposal_id, vote: Vote::Yes };
+        let res = handle(&mut deps, mock_env(VOTER1, &[]), vote).unwrap();
+        assert_eq!(res.log[3].value, "Passed");
+        assert_eq!(get_tally(&deps, proposal_id), u64::MAX - 1);
+    }
 
-        // Non-expired proposals cannot be closed
-        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), closing).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::NotExpired {}.to_string()));
 
-        // Expired proposals can be closed
-        let proposal = HandleMsg::Propose {
-            title: "(Try to) pay somebody".to_string(),
-            description: "Pay somebody after time?".to_string(),
-            msgs,
-            latest: Some(Expiration::AtHeight(123456)),
+    #[test]
+    fn test_vote_batch_late_failure_writes_nothing() {
+        let mut deps = mock_dependencies(20, &[]);
+        let info = MessageInfo {sender: HumanAddr::from(OWNER), sent_funds: vec![]};
+        setup_test_case(&mut deps, info, Threshold::AbsoluteCount { weight: 15 }, Duration::Time(2000000)).unwrap();
+
+        let delegate = HandleMsg::Delegate { to: VOTER2.to_string(), until: None };
+        handle(&mut deps, mock_env(VOTER1, &[]), delegate).unwrap();
+        let proposal = HandleMsg::ProposeAction {
+            title: "Routine".to_string(),
+            description: "Monthly approval".to_string(),
+            action: ProposalAction::Signal {},
+            latest: None,
+            execute_at: None,
         };
-        let res = handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
-
-        // Get the proposal id from the logs
-        let proposal_id: u64 = res.log[2].value.parse().unwrap();
-
-        let closing = HandleMsg::Close { proposal_id };
-
-        // Close expired works
-        let env = mock_env_height(1234567);
-        let res = handle(
-            &mut deps,
-            env,
-            closing.clone(),
-        )
-        .unwrap();
-
-        // Verify
-        assert_eq!(
-            res,
-            HandleResponse {
-                messages: vec![],
-                log: vec![
-                    log("action","close"),
-                    log("sender", OWNER),
-                    log("proposal_id", proposal_id.to_string())],
-                data: None
-            }
-        );
-
-        // Trying to close it again fails
-        let err = handle(&mut deps, mock_env(SOMEBODY, &[]), closing).unwrap_err();
-        assert_eq!(err, StdError::generic_err(ContractError::WrongCloseStatus {}.to_string()));
+        handle(&mut deps, mock_env(OWNER, &[]), proposal).unwrap();
+
+        // the no tally has room for the delegated weight, but not for the voter's own
+        let mut prop = proposals_read(&deps.storage).load(&1u64.to_le_bytes()).unwrap();
+        prop.votes.no = u64::MAX - 1;
+        proposals(&mut deps.storage).save(&1u64.to_le_bytes(), &prop).unwrap();
+
+        let batch = HandleMsg::VoteBatch { votes: vec![(1, Vote::No)], mode: VoteBatchMode::BestEffort };
+        let res = handle(&mut deps, mock_env(VOTER2, &[]), batch).unwrap();
+        assert_eq!(res.log[3], log("failed", "1"));
+        let data: VoteBatchResponse = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(data.results[0].error, Some(StdError::generic_err(ContractError::TallyOverflow {}.to_string()).to_string()));
+
+        // neither the ballot, the delegated vote nor the tally was written
+        let voter2_key = member_key(&deps.storage, &deps.api, &HumanAddr::from(VOTER2)).unwrap();
+        assert_eq!(ballots_read(&deps.storage, 1).may_load(voter2_key.as_slice()).unwrap(), None);
+        assert_eq!(delegated_votes_read(&deps.storage, 1).may_load(VOTER1.as_bytes()).unwrap(), None);
+        assert_eq!(proposals_read(&deps.storage).load(&1u64.to_le_bytes()).unwrap(), prop);
+
+        // so the delegator's weight is counted once when they vote themselves
+        handle(&mut deps, mock_env(VOTER1, &[]), HandleMsg::Vote { proposal_id: 1, vote: Vote::Yes }).unwrap();
+        assert_eq!(get_tally(&deps, 1), 2);
     }
 }
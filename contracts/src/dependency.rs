@@ -0,0 +1,80 @@
+use cosmwasm_std::{StdError, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::query::Status;
+use crate::state::{archived_proposals_read, dependents_read, proposals_read};
+
+/// Most proposals a proposal can depend on
+pub const MAX_DEPENDENCIES: usize = 16;
+
+/// Checks the dependencies of the new proposal `proposal_id`, returning them sorted and
+/// without duplicates. They must exist and not be rejected. Existing proposals are older
+/// than the new one, so only a dependency on itself could form a cycle.
+pub fn validate_dependencies<S: Storage>(
+    storage: &S,
+    proposal_id: u64,
+    mut depends_on: Vec<u64>,
+) -> StdResult<Vec<u64>> {
+    depends_on.sort_unstable();
+    depends_on.dedup();
+    if depends_on.len() > MAX_DEPENDENCIES {
+        return Err(error(ContractError::TooManyDependencies { max: MAX_DEPENDENCIES }));
+    }
+
+    if depends_on.contains(&proposal_id) {
+        return Err(error(ContractError::SelfDependency {}));
+    }
+
+    for id in depends_on.iter() {
+        match stored_status(storage, *id)? {
+            None => return Err(error(ContractError::UnknownDependency { id: *id })),
+            Some(Status::Rejected) => return Err(error(ContractError::DependencyRejected { id: *id })),
+            Some(_) => {}
+        }
+    }
+    Ok(depends_on)
+}
+
+/// Fails unless every proposal of `depends_on` was executed
+pub fn check_dependencies_executed<S: Storage>(storage: &S, depends_on: &[u64]) -> StdResult<()> {
+    for id in depends_on {
+        if stored_status(storage, *id)? != Some(Status::Executed) {
+            return Err(error(ContractError::DependencyNotExecuted { id: *id }));
+        }
+    }
+    Ok(())
+}
+
+/// Proposals still open or passed that depend on `proposal_id`, directly or through other
+/// proposals, in the order they are reached
+pub fn pending_dependents<S: Storage>(storage: &S, proposal_id: u64) -> StdResult<Vec<u64>> {
+    let mut pending = vec![];
+    let mut visited = vec![proposal_id];
+    let mut queue = vec![proposal_id];
+    while !queue.is_empty() {
+        let id = queue.remove(0);
+        for dependent in dependents_read(storage).may_load(&id.to_le_bytes())?.unwrap_or_default() {
+            if visited.contains(&dependent) {
+                continue;
+            }
+            visited.push(dependent);
+            queue.push(dependent);
+            if let Some(Status::Open) | Some(Status::Passed) = stored_status(storage, dependent)? {
+                pending.push(dependent);
+            }
+        }
+    }
+    Ok(pending)
+}
+
+// status of a proposal, pruned or not
+fn stored_status<S: Storage>(storage: &S, id: u64) -> StdResult<Option<Status>> {
+    if let Some(prop) = proposals_read(storage).may_load(&id.to_le_bytes())? {
+        return Ok(Some(prop.status));
+    }
+    Ok(archived_proposals_read(storage).may_load(&id.to_le_bytes())?.map(|archived| archived.status))
+}
+
+fn error(err: ContractError) -> StdError {
+    StdError::generic_err(err.to_string())
+}
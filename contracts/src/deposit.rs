@@ -0,0 +1,243 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{BankMsg, Coin, CosmosMsg, HumanAddr, StdError, StdResult};
+use secret_toolkit::snip20::transfer_msg;
+
+use crate::error::ContractError;
+use crate::math::Uint128;
+use crate::query::Status;
+use crate::state::Votes;
+
+/// pad handle responses sent to SNIP-20 contracts to this block size
+pub const BLOCK_SIZE: usize = 256;
+
+/// The token a proposal deposit is paid in
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositToken {
+    /// Sent along with `Propose` as native funds
+    Native { denom: String },
+    /// Sent through the SNIP-20 `Send` to this contract, which calls back `Receive`
+    Snip20 { address: HumanAddr, code_hash: String },
+}
+
+/// Decides what happens to a deposit once the proposal is executed or rejected.
+/// Forfeited deposits stay in the multisig.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositRefundPolicy {
+    /// Always refund the proposer
+    Always,
+    /// Only refund proposals that passed and were executed. A passed proposal rejected
+    /// along with one of its dependencies is not refunded
+    OnlyPassed,
+    /// Refund unless the proposal was rejected with more than a third of the
+    /// cast weight voting Veto
+    UnlessVetoed,
+    /// Never refund
+    Never,
+}
+
+impl DepositRefundPolicy {
+    /// returns true if a deposit should be given back, given the final status and tally
+    pub fn should_refund(&self, status: Status, votes: &Votes) -> bool {
+        match self {
+            DepositRefundPolicy::Always => true,
+            DepositRefundPolicy::OnlyPassed => status == Status::Executed,
+            DepositRefundPolicy::UnlessVetoed => {
+                status == Status::Executed || (votes.veto as u128) * 3 <= votes.total().u128()
+            }
+            DepositRefundPolicy::Never => false,
+        }
+    }
+}
+
+/// Deposit required to create a proposal
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ProposalDeposit {
+    pub token: DepositToken,
+    pub amount: Uint128,
+    pub refund_policy: DepositRefundPolicy,
+}
+
+impl ProposalDeposit {
+    pub fn validate(&self) -> StdResult<()> {
+        if self.amount.is_zero() {
+            return Err(StdError::generic_err(ContractError::ZeroDeposit {}.to_string()));
+        }
+        Ok(())
+    }
+
+    /// checks the funds sent along with `Propose` pay exactly this deposit
+    pub fn check_native_funds(&self, funds: &[Coin]) -> StdResult<()> {
+        let denom = match &self.token {
+            DepositToken::Native { denom } => denom,
+            // SNIP-20 deposits can only come in through `Receive`
+            DepositToken::Snip20 { .. } => {
+                return Err(StdError::generic_err(ContractError::DepositRequired {}.to_string()))
+            }
+        };
+        match funds {
+            [] => Err(StdError::generic_err(ContractError::DepositRequired {}.to_string())),
+            [coin] if &coin.denom == denom && coin.amount.u128() == self.amount.u128() => Ok(()),
+            _ => Err(StdError::generic_err(ContractError::InvalidDeposit {}.to_string())),
+        }
+    }
+
+    /// checks a SNIP-20 `Receive` from `token` pays exactly this deposit
+    pub fn check_snip20_funds(&self, token: &HumanAddr, amount: Uint128) -> StdResult<()> {
+        match &self.token {
+            DepositToken::Snip20 { address, .. } if address == token && amount == self.amount => Ok(()),
+            _ => Err(StdError::generic_err(ContractError::InvalidDeposit {}.to_string())),
+        }
+    }
+
+    /// the record kept while the proposal of `depositor` is pending
+    pub fn paid_by(&self, depositor: HumanAddr) -> Deposit {
+        Deposit {
+            depositor,
+            token: self.token.clone(),
+            amount: self.amount,
+            refund_policy: self.refund_policy,
+        }
+    }
+}
+
+/// A deposit held by the contract until its proposal is executed or rejected
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Deposit {
+    pub depositor: HumanAddr,
+    pub token: DepositToken,
+    pub amount: Uint128,
+    pub refund_policy: DepositRefundPolicy,
+}
+
+impl Deposit {
+    /// cw3's description of this deposit
+    pub fn info(&self) -> DepositInfo {
+        let denom = match &self.token {
+            DepositToken::Native { denom } => Denom::Native(denom.clone()),
+            DepositToken::Snip20 { address, .. } => Denom::Cw20(address.clone()),
+        };
+        DepositInfo {
+            amount: self.amount,
+            denom,
+            refund_failed_proposals: match self.refund_policy {
+                DepositRefundPolicy::Always | DepositRefundPolicy::UnlessVetoed => true,
+                DepositRefundPolicy::OnlyPassed | DepositRefundPolicy::Never => false,
+            },
+        }
+    }
+
+    /// message sending the deposit back from `contract` to the depositor
+    pub fn refund_msg(&self, contract: &HumanAddr) -> StdResult<CosmosMsg> {
+        let amount = cosmwasm_std::Uint128(self.amount.u128());
+        match &self.token {
+            DepositToken::Native { denom } => Ok(CosmosMsg::Bank(BankMsg::Send {
+                from_address: contract.clone(),
+                to_address: self.depositor.clone(),
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }],
+            })),
+            DepositToken::Snip20 { address, code_hash } => transfer_msg(
+                self.depositor.clone(),
+                amount,
+                None,
+                None,
+                BLOCK_SIZE,
+                code_hash.clone(),
+                address.clone(),
+            ),
+        }
+    }
+}
+
+/// The deposit of a proposal as cw3 describes it, for cw3 tooling. The refund policy can
+/// only be approximated: `refund_failed_proposals` is set when rejected proposals are
+/// (at least sometimes) refunded, see `Deposit.refund_policy` for the exact one.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositInfo {
+    pub amount: Uint128,
+    pub denom: Denom,
+    pub refund_failed_proposals: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Denom {
+    Native(String),
+    /// A SNIP-20 token, under the name cw3 gives to token contracts
+    Cw20(HumanAddr),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    fn native_deposit(amount: u128) -> ProposalDeposit {
+        ProposalDeposit {
+            token: DepositToken::Native {
+                denom: "uscrt".to_string(),
+            },
+            amount: Uint128(amount),
+            refund_policy: DepositRefundPolicy::Always,
+        }
+    }
+
+    #[test]
+    fn validate_deposit() {
+        let err = native_deposit(0).validate().unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::ZeroDeposit {}.to_string()));
+        native_deposit(1).validate().unwrap();
+    }
+
+    #[test]
+    fn check_native_funds() {
+        let deposit = native_deposit(100);
+        deposit.check_native_funds(&[coin(100, "uscrt")]).unwrap();
+
+        let err = deposit.check_native_funds(&[]).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DepositRequired {}.to_string()));
+
+        // wrong amount, wrong denom and extra coins are all rejected
+        for funds in &[
+            vec![coin(99, "uscrt")],
+            vec![coin(100, "BTC")],
+            vec![coin(100, "uscrt"), coin(1, "BTC")],
+        ] {
+            let err = deposit.check_native_funds(funds).unwrap_err();
+            assert_eq!(err, StdError::generic_err(ContractError::InvalidDeposit {}.to_string()));
+        }
+    }
+
+    #[test]
+    fn refund_policies() {
+        let vetoed = Votes {
+            yes: 3,
+            no: 2,
+            abstain: 0,
+            veto: 4,
+        };
+        let rejected = Votes {
+            yes: 3,
+            no: 4,
+            abstain: 0,
+            veto: 2,
+        };
+
+        assert!(DepositRefundPolicy::Always.should_refund(Status::Rejected, &vetoed));
+        assert!(!DepositRefundPolicy::Never.should_refund(Status::Executed, &rejected));
+
+        assert!(DepositRefundPolicy::OnlyPassed.should_refund(Status::Executed, &vetoed));
+        assert!(!DepositRefundPolicy::OnlyPassed.should_refund(Status::Rejected, &rejected));
+
+        // 4 of 9 is more than a third
+        assert!(!DepositRefundPolicy::UnlessVetoed.should_refund(Status::Rejected, &vetoed));
+        assert!(DepositRefundPolicy::UnlessVetoed.should_refund(Status::Rejected, &rejected));
+        assert!(DepositRefundPolicy::UnlessVetoed.should_refund(Status::Executed, &vetoed));
+    }
+}
@@ -17,12 +17,117 @@ pub enum ContractError {
     #[error("Not possible to reach required (passing) weight")]
     UnreachableWeight {},
 
+    #[error("Total weight of the voters is too large")]
+    WeightOverflow {},
+
     #[error("No voters")]
     NoVoters {},
 
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Voters must be unique")]
+    DuplicateVoter {},
+
+    #[error("Title is longer than {max} bytes")]
+    TitleTooLong { max: usize },
+
+    #[error("Description is longer than {max} bytes")]
+    DescriptionTooLong { max: usize },
+
+    #[error("Only signalling proposals can have no messages")]
+    EmptyProposal {},
+
+    #[error("Proposals cannot have more than {max} messages")]
+    TooManyMessages { max: usize },
+
+    #[error("Proposal messages are larger than {max} bytes")]
+    MessagesTooLarge { max: usize },
+
+    #[error("Proposals can only send funds of the multisig")]
+    InvalidSender {},
+
+    #[error("Invalid recipient address {address}")]
+    InvalidRecipient { address: String },
+
+    #[error("Proposers must be members of the multisig")]
+    UnknownProposer {},
+
+    #[error("Proposal deposit cannot be zero")]
+    ZeroDeposit {},
+
+    #[error("A deposit is required to create a proposal")]
+    DepositRequired {},
+
+    #[error("Sent funds do not match the required proposal deposit")]
+    InvalidDeposit {},
+
+    #[error("Houses must have distinct groups")]
+    DuplicateHouse {},
+
+    #[error("Houses must have members with weight")]
+    EmptyHouse {},
+
+    #[error("Hook is already registered")]
+    HookAlreadyRegistered {},
+
+    #[error("Hook is not registered")]
+    HookNotRegistered {},
+
+    #[error("Cannot delegate to yourself")]
+    SelfDelegation {},
+
+    #[error("Voting power can only be delegated to members of the multisig")]
+    InvalidDelegate {},
+
+    #[error("Delegation would already be expired")]
+    DelegationExpired {},
+
+    #[error("No voting power delegated")]
+    NotDelegated {},
+
+    #[error("Only guardians can pause the multisig")]
+    NotGuardian {},
+
+    #[error("Guardian's last pause was lifted, it must be re-appointed to pause again")]
+    GuardianOverruled {},
+
+    #[error("Pause would already be expired")]
+    PauseExpired {},
+
+    #[error("Multisig is not paused")]
+    NotPaused {},
+
+    #[error("Multisig is paused, proposals cannot be created")]
+    ProposePaused {},
+
+    #[error("Multisig is paused, votes cannot be cast")]
+    VotePaused {},
+
+    #[error("Multisig is paused, proposals cannot be executed")]
+    ExecutePaused {},
+
+    #[error("State import is not open")]
+    ImportClosed {},
+
+    #[error("State is being imported, no proposal can be made")]
+    ImportInProgress {},
+
+    #[error("Unsupported snapshot version")]
+    UnsupportedSnapshot {},
+
+    #[error("Snapshot proposals must be imported in order")]
+    SnapshotOutOfOrder {},
+
+    #[error("Snapshot voters or total weight differ from the contract's")]
+    SnapshotMismatch {},
+
+    #[error("Snapshot has a ballot from a voter that is not a member")]
+    SnapshotUnknownVoter {},
+
+    #[error("Imported proposals cannot be voted on or executed")]
+    ImportedProposal {},
+
     #[error("Proposal is not open")]
     NotOpen {},
 
@@ -38,9 +143,63 @@ pub enum ContractError {
     #[error("Already voted on this proposal")]
     AlreadyVoted {},
 
+    #[error("Vote weight overflows the tally")]
+    TallyOverflow {},
+
+    #[error("Cannot take back more weight than was voted")]
+    TallyUnderflow {},
+
+    #[error("Cannot vote on more than {max} proposals at once")]
+    TooManyVotes { max: usize },
+
+    #[error("Public key must be a compressed secp256k1 key")]
+    InvalidPubkey {},
+
+    #[error("Signature does not match the vote")]
+    InvalidSignature {},
+
+    #[error("Nonce {nonce} was already used")]
+    NonceUsed { nonce: u64 },
+
     #[error("Proposal must have passed and not yet been executed")]
     WrongExecuteStatus {},
 
+    #[error("Proposal is not executed step by step")]
+    NotStepwise {},
+
+    #[error("Proposal has no step {step}")]
+    InvalidStep { step: u32 },
+
+    #[error("Step {step} was already executed")]
+    StepAlreadyExecuted { step: u32 },
+
+    #[error("Execution can only be scheduled at a block height or time")]
+    InvalidExecuteAt {},
+
+    #[error("Proposal is scheduled to be executed later")]
+    NotDue {},
+
+    #[error("Cannot depend on more than {max} proposals")]
+    TooManyDependencies { max: usize },
+
+    #[error("Dependency {id} does not exist")]
+    UnknownDependency { id: u64 },
+
+    #[error("Dependency {id} was rejected")]
+    DependencyRejected { id: u64 },
+
+    #[error("Proposal cannot depend on itself")]
+    SelfDependency {},
+
+    #[error("Dependency {id} has not been executed yet")]
+    DependencyNotExecuted { id: u64 },
+
     #[error("Cannot close completed or passed proposals")]
     WrongCloseStatus {},
+
+    #[error("Contract address is not recorded yet, the next handle message saves it")]
+    UnknownContractAddress {},
+
+    #[error("Total amount sent is too large")]
+    AmountOverflow {},
 }
@@ -0,0 +1,108 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{from_binary, to_binary, CosmosMsg, Empty, HumanAddr, StdResult, Storage, WasmMsg};
+
+use crate::msg::HandleMsg;
+use crate::query::Status;
+use crate::state::{hooks_read, Proposal};
+
+/// A contract notified of proposal events
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Hook {
+    pub address: HumanAddr,
+    pub code_hash: String,
+}
+
+/// Proposal events sent to hooks
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalHookMsg {
+    /// A proposal was created, with the status it got from the proposer's vote
+    NewProposal {
+        proposal_id: u64,
+        proposer: String,
+        status: Status,
+    },
+    /// A proposal changed status, after a vote, an execution or a close
+    ProposalStatusChanged {
+        proposal_id: u64,
+        old_status: Status,
+        new_status: Status,
+    },
+}
+
+/// The message hooks receive, so they only need a `proposal_hook` variant in their
+/// own `HandleMsg`
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HookHandleMsg {
+    ProposalHook(ProposalHookMsg),
+}
+
+impl ProposalHookMsg {
+    /// message notifying `hook` of this event
+    pub fn into_cosmos_msg(self, hook: &Hook) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: hook.address.clone(),
+            callback_code_hash: hook.code_hash.clone(),
+            msg: to_binary(&HookHandleMsg::ProposalHook(self))?,
+            send: vec![],
+        }))
+    }
+}
+
+/// Returns true if all of `msgs` remove hooks of the multisig at `contract`. Hooks are not
+/// notified of those proposals, so that a failing hook cannot block its own removal.
+pub fn is_remove_hook_proposal(msgs: &[CosmosMsg<Empty>], contract: &HumanAddr) -> bool {
+    !msgs.is_empty()
+        && msgs.iter().all(|msg| match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                contract_addr == contract
+                    && matches!(from_binary(msg), Ok(HandleMsg::RemoveHook { .. }))
+            }
+            _ => false,
+        })
+}
+
+/// messages notifying every registered hook of `event` on `prop`, unless it removes hooks
+pub fn hook_msgs<S: Storage>(
+    storage: &S,
+    contract: &HumanAddr,
+    prop: &Proposal,
+    event: ProposalHookMsg,
+) -> StdResult<Vec<CosmosMsg>> {
+    if is_remove_hook_proposal(&prop.msgs, contract) {
+        return Ok(vec![]);
+    }
+    hooks_read(storage)
+        .may_load()?
+        .unwrap_or_default()
+        .iter()
+        .map(|hook| event.clone().into_cosmos_msg(hook))
+        .collect()
+}
+
+/// messages notifying every registered hook that `prop` went from `old_status` to its
+/// current status, if they differ
+pub fn status_change_msgs<S: Storage>(
+    storage: &S,
+    contract: &HumanAddr,
+    proposal_id: u64,
+    prop: &Proposal,
+    old_status: Status,
+) -> StdResult<Vec<CosmosMsg>> {
+    if old_status == prop.status {
+        return Ok(vec![]);
+    }
+    hook_msgs(
+        storage,
+        contract,
+        prop,
+        ProposalHookMsg::ProposalStatusChanged {
+            proposal_id,
+            old_status,
+            new_status: prop.status,
+        },
+    )
+}
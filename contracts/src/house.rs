@@ -0,0 +1,116 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{StdError, StdResult};
+
+use crate::error::ContractError;
+use crate::msg::Voter;
+use crate::state::Votes;
+use crate::threshold::{Threshold, ThresholdResponse};
+
+/// A group of members that must approve every proposal on its own, in addition to
+/// the multisig threshold. Members join a house through the `group` of their `Voter`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct House {
+    pub group: String,
+    pub threshold: Threshold,
+}
+
+impl House {
+    /// total weight of the members in this house
+    pub fn total_weight(&self, voters: &[Voter]) -> u64 {
+        voters
+            .iter()
+            .filter(|v| v.group.as_deref() == Some(self.group.as_str()))
+            .map(|v| v.weight)
+            .sum()
+    }
+
+    /// an empty tally of this house for a new proposal
+    pub fn tally(&self, voters: &[Voter]) -> HouseTally {
+        HouseTally {
+            group: self.group.clone(),
+            threshold: self.threshold.clone(),
+            total_weight: self.total_weight(voters),
+            votes: Votes::yes(0),
+        }
+    }
+}
+
+/// Validates that houses are unique and that each threshold is reachable by its members
+pub fn validate_houses(houses: &[House], voters: &[Voter]) -> StdResult<()> {
+    for (i, house) in houses.iter().enumerate() {
+        if houses[..i].iter().any(|h| h.group == house.group) {
+            return Err(StdError::generic_err(ContractError::DuplicateHouse {}.to_string()));
+        }
+        let total_weight = house.total_weight(voters);
+        if total_weight == 0 {
+            return Err(StdError::generic_err(ContractError::EmptyHouse {}.to_string()));
+        }
+        house.threshold.validate(total_weight)?;
+    }
+    Ok(())
+}
+
+/// Votes cast on a proposal by the members of a house, with the requirement they must meet
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HouseTally {
+    pub group: String,
+    pub threshold: Threshold,
+    // the total weight of the house when the proposal started
+    pub total_weight: u64,
+    pub votes: Votes,
+}
+
+/// Pass requirement of a single house
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HouseThresholdResponse {
+    pub group: String,
+    pub threshold: ThresholdResponse,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voter(addr: &str, weight: u64, group: Option<&str>) -> Voter {
+        Voter {
+            addr: addr.to_string(),
+            weight,
+            group: group.map(|g| g.to_string()),
+        }
+    }
+
+    #[test]
+    fn validate_houses_works() {
+        let voters = vec![
+            voter("alice", 1, Some("board")),
+            voter("bob", 2, Some("board")),
+            voter("carol", 3, Some("tech")),
+            voter("dave", 4, None),
+        ];
+        let board = House {
+            group: "board".to_string(),
+            threshold: Threshold::AbsoluteCount { weight: 3 },
+        };
+        assert_eq!(board.total_weight(&voters), 3);
+        validate_houses(std::slice::from_ref(&board), &voters).unwrap();
+
+        let err = validate_houses(&[board.clone(), board.clone()], &voters).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::DuplicateHouse {}.to_string()));
+
+        let nobody = House {
+            group: "nobody".to_string(),
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+        };
+        let err = validate_houses(&[nobody], &voters).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::EmptyHouse {}.to_string()));
+
+        // dave's weight doesn't count for tech
+        let tech = House {
+            group: "tech".to_string(),
+            threshold: Threshold::AbsoluteCount { weight: 4 },
+        };
+        validate_houses(&[board, tech], &voters).unwrap_err();
+    }
+}
@@ -1,16 +1,29 @@
+pub mod archive;
 pub mod contract;
+pub mod dependency;
+pub mod deposit;
 mod error;
 pub mod expiration;
+pub mod hooks;
+pub mod house;
 mod math;
 pub mod msg;
+pub mod nested;
+pub mod pause;
 pub mod query;
+pub mod rules;
+pub mod signed;
+pub mod simulate;
+pub mod snapshot;
 pub mod state;
 pub mod threshold;
+pub mod upgrade;
+pub mod validation;
 
 pub use crate::error::ContractError;
 pub use crate::math::{ Decimal, Uint128 };
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "library")))]
 mod wasm {
     use super::contract;
     use cosmwasm_std::{
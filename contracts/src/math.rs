@@ -52,6 +52,70 @@ impl Decimal {
     pub fn is_zero(&self) -> bool {
         self.0 == 0
     }
+
+    /// Returns self + other, or None if it overflows
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_add(other.0).map(Decimal)
+    }
+
+    /// Returns self - other, or None if other is greater
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_sub(other.0).map(Decimal)
+    }
+
+    /// Returns self * other rounded down, or None if it overflows
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        Uint256::full_mul(self.0, other.0)
+            .checked_div_u128(DECIMAL_FRACTIONAL)
+            .map(Decimal)
+    }
+
+    /// Returns self / other rounded down, or None if it overflows or other is zero
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        Uint256::full_mul(self.0, DECIMAL_FRACTIONAL)
+            .checked_div_u128(other.0)
+            .map(Decimal)
+    }
+
+    /// The greatest whole number not above self
+    pub fn floor(&self) -> Uint128 {
+        Uint128(self.0 / DECIMAL_FRACTIONAL)
+    }
+
+    /// The smallest whole number not below self
+    pub fn ceil(&self) -> Uint128 {
+        Uint128(self.0.div_ceil(DECIMAL_FRACTIONAL))
+    }
+
+    /// Square root rounded down. Values above 340 lose some of the 18 fractional digits,
+    /// as sqrt(self) is computed from sqrt(self * 10^(2n)) / 10^n with the largest n that fits.
+    pub fn sqrt(&self) -> Decimal {
+        // sqrt(x / 10^18) * 10^18 == sqrt(x * 10^18)
+        (0..=9u32)
+            .rev()
+            .find_map(|n| {
+                self.0
+                    .checked_mul(10u128.pow(2 * n))
+                    .map(|scaled| Decimal(isqrt(scaled) * 10u128.pow(9 - n)))
+            })
+            .unwrap_or_default()
+    }
+}
+
+// integer square root rounded down, by Newton's method
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // start above the root, so that the estimates decrease towards it
+    let mut x = 1u128 << ((128 - n.leading_zeros()) / 2 + 1);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
 }
 
 impl FromStr for Decimal {
@@ -136,6 +200,14 @@ impl ops::Sub for Decimal {
     }
 }
 
+impl ops::Mul for Decimal {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other).expect("Decimal multiplication overflow")
+    }
+}
+
 /// Serializes as a decimal string
 impl Serialize for Decimal {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -194,6 +266,26 @@ impl Uint128 {
     pub fn is_zero(&self) -> bool {
         self.0 == 0
     }
+
+    /// Returns self + other, or None if it overflows
+    pub fn checked_add(self, other: Uint128) -> Option<Uint128> {
+        self.0.checked_add(other.0).map(Uint128)
+    }
+
+    /// Returns self - other, or None if other is greater
+    pub fn checked_sub(self, other: Uint128) -> Option<Uint128> {
+        self.0.checked_sub(other.0).map(Uint128)
+    }
+
+    /// Returns self * other, or None if it overflows
+    pub fn checked_mul(self, other: Uint128) -> Option<Uint128> {
+        self.0.checked_mul(other.0).map(Uint128)
+    }
+
+    /// Returns self / other rounded down, or None if other is zero
+    pub fn checked_div(self, other: Uint128) -> Option<Uint128> {
+        self.0.checked_div(other.0).map(Uint128)
+    }
 }
 
 impl From<u128> for Uint128 {
@@ -219,12 +311,14 @@ impl TryFrom<&str> for Uint128 {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<String> for Uint128 {
     fn into(self) -> String {
         self.0.to_string()
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<u128> for Uint128 {
     fn into(self) -> u128 {
         self.0
@@ -296,9 +390,33 @@ impl Uint128 {
         if denominator == 0 {
             panic!("Denominator must not be zero");
         }
-        // TODO: minimize rounding that takes place (using gcd algorithm)
-        let val = self.u128() * nominator / denominator;
-        Uint128::from(val)
+        self.checked_multiply_ratio(nominator, denominator)
+            .expect("multiply_ratio overflow")
+    }
+
+    /// returns self * nom / denom rounded down, or None if the result overflows or denom
+    /// is zero. The product itself can't overflow, it is computed on 256 bits.
+    pub fn checked_multiply_ratio<A: Into<u128>, B: Into<u128>>(&self, nom: A, denom: B) -> Option<Uint128> {
+        Uint256::full_mul(self.0, nom.into())
+            .checked_div_u128(denom.into())
+            .map(Uint128)
+    }
+
+    /// returns self * nom / denom rounded up, or None if the result overflows or denom is zero
+    pub fn checked_multiply_ratio_ceil<A: Into<u128>, B: Into<u128>>(&self, nom: A, denom: B) -> Option<Uint128> {
+        Uint256::full_mul(self.0, nom.into())
+            .checked_div_ceil_u128(denom.into())
+            .map(Uint128)
+    }
+
+    /// returns self * rhs rounded down, or None if it overflows
+    pub fn checked_mul_floor(&self, rhs: Decimal) -> Option<Uint128> {
+        self.checked_multiply_ratio(rhs.0, DECIMAL_FRACTIONAL)
+    }
+
+    /// returns self * rhs rounded up, or None if it overflows
+    pub fn checked_mul_ceil(&self, rhs: Decimal) -> Option<Uint128> {
+        self.checked_multiply_ratio_ceil(rhs.0, DECIMAL_FRACTIONAL)
     }
 }
 
@@ -342,7 +460,99 @@ impl<'de> de::Visitor<'de> for Uint128Visitor {
     }
 }
 
+//*** Uint256 ***/
+/// Only used for the intermediate results of 128 bit math, such as the product in
+/// `multiply_ratio`, so it is never stored or serialized
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Uint256 {
+    // the field order makes the derived ordering compare the high half first
+    hi: u128,
+    lo: u128,
+}
+
+impl Uint256 {
+    const HALF_BITS: u32 = 64;
+    const LOW_MASK: u128 = u64::MAX as u128;
+
+    /// Returns a * b, which always fits
+    pub fn full_mul(a: u128, b: u128) -> Uint256 {
+        let (a_hi, a_lo) = (a >> Self::HALF_BITS, a & Self::LOW_MASK);
+        let (b_hi, b_lo) = (b >> Self::HALF_BITS, b & Self::LOW_MASK);
+
+        // none of the partial products of 64 bit halves can overflow
+        let low = a_lo * b_lo;
+        let (mid, mid_carry) = (a_lo * b_hi).overflowing_add(a_hi * b_lo);
+        let high = a_hi * b_hi;
+
+        let (lo, lo_carry) = low.overflowing_add(mid << Self::HALF_BITS);
+        let hi = high
+            + (mid >> Self::HALF_BITS)
+            + ((mid_carry as u128) << Self::HALF_BITS)
+            + lo_carry as u128;
+        Uint256 { hi, lo }
+    }
+
+    /// Returns the quotient and remainder of self / divisor, or None if divisor is zero
+    pub fn div_rem(self, divisor: u128) -> Option<(Uint256, u128)> {
+        if divisor == 0 {
+            return None;
+        }
+        // long division, one bit at a time from the highest
+        let mut quotient = Uint256::default();
+        let mut rem: u128 = 0;
+        for i in (0..256).rev() {
+            let bit = if i >= 128 { (self.hi >> (i - 128)) & 1 } else { (self.lo >> i) & 1 };
+            // rem < divisor, so 2 * rem + bit only exceeds 128 bits when it is above divisor
+            let carry = rem >> 127;
+            rem = (rem << 1) | bit;
+            if carry == 1 || rem >= divisor {
+                rem = rem.wrapping_sub(divisor);
+                if i >= 128 {
+                    quotient.hi |= 1 << (i - 128);
+                } else {
+                    quotient.lo |= 1 << i;
+                }
+            }
+        }
+        Some((quotient, rem))
+    }
+
+    /// Returns self / divisor rounded down, or None if divisor is zero or the result
+    /// doesn't fit 128 bits
+    pub fn checked_div_u128(self, divisor: u128) -> Option<u128> {
+        let (quotient, _) = self.div_rem(divisor)?;
+        quotient.checked_into_u128()
+    }
+
+    /// Returns self / divisor rounded up, or None if divisor is zero or the result
+    /// doesn't fit 128 bits
+    pub fn checked_div_ceil_u128(self, divisor: u128) -> Option<u128> {
+        let (quotient, rem) = self.div_rem(divisor)?;
+        let quotient = quotient.checked_into_u128()?;
+        if rem == 0 {
+            Some(quotient)
+        } else {
+            quotient.checked_add(1)
+        }
+    }
+
+    /// Returns the value if it fits 128 bits
+    pub fn checked_into_u128(self) -> Option<u128> {
+        match self.hi {
+            0 => Some(self.lo),
+            _ => None,
+        }
+    }
+}
+
+impl From<u128> for Uint256 {
+    fn from(val: u128) -> Self {
+        Uint256 { hi: 0, lo: val }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::zero_prefixed_literal, clippy::bool_assert_comparison)]
 mod test {
     use super::*;
     use cosmwasm_std::{StdError, StdResult};
@@ -556,6 +766,65 @@ mod test {
         assert_eq!(value.0, DECIMAL_FRACTIONAL * 3 / 2);
     }
 
+    #[test]
+    fn decimal_checked_add_sub() {
+        assert_eq!(Decimal::one().checked_add(Decimal::percent(50)), Some(Decimal::percent(150)));
+        assert_eq!(Decimal::MAX.checked_add(Decimal(1)), None);
+        assert_eq!(Decimal::one().checked_sub(Decimal::percent(25)), Some(Decimal::percent(75)));
+        assert_eq!(Decimal::percent(25).checked_sub(Decimal::one()), None);
+    }
+
+    #[test]
+    fn decimal_mul() {
+        assert_eq!(Decimal::percent(150) * Decimal::percent(200), Decimal::percent(300));
+        assert_eq!(Decimal::percent(50) * Decimal::percent(50), Decimal::percent(25));
+        assert_eq!(Decimal::MAX * Decimal::one(), Decimal::MAX);
+        assert_eq!(Decimal::MAX * Decimal::zero(), Decimal::zero());
+        // rounds down below the 18th fractional digit
+        assert_eq!(Decimal(1) * Decimal::percent(50), Decimal::zero());
+
+        assert_eq!(Decimal::MAX.checked_mul(Decimal::percent(101)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Decimal multiplication overflow")]
+    fn decimal_mul_panics_on_overflow() {
+        let _ = Decimal::MAX * Decimal::percent(200);
+    }
+
+    #[test]
+    fn decimal_checked_div() {
+        assert_eq!(Decimal::percent(300).checked_div(Decimal::percent(200)), Some(Decimal::percent(150)));
+        assert_eq!(Decimal::one().checked_div(Decimal::from_ratio(3u128, 1u128)), Some(Decimal(333_333_333_333_333_333)));
+        assert_eq!(Decimal::MAX.checked_div(Decimal::one()), Some(Decimal::MAX));
+        assert_eq!(Decimal::MAX.checked_div(Decimal::percent(50)), None);
+        assert_eq!(Decimal::one().checked_div(Decimal::zero()), None);
+    }
+
+    #[test]
+    fn decimal_floor_ceil() {
+        assert_eq!(Decimal::percent(150).floor(), Uint128(1));
+        assert_eq!(Decimal::percent(150).ceil(), Uint128(2));
+        assert_eq!(Decimal::percent(200).floor(), Uint128(2));
+        assert_eq!(Decimal::percent(200).ceil(), Uint128(2));
+        assert_eq!(Decimal(1).floor(), Uint128(0));
+        assert_eq!(Decimal(1).ceil(), Uint128(1));
+        assert_eq!(Decimal::MAX.floor(), Uint128(u128::MAX / DECIMAL_FRACTIONAL));
+        assert_eq!(Decimal::MAX.ceil(), Uint128(u128::MAX / DECIMAL_FRACTIONAL + 1));
+    }
+
+    #[test]
+    fn decimal_sqrt() {
+        assert_eq!(Decimal::zero().sqrt(), Decimal::zero());
+        assert_eq!(Decimal::one().sqrt(), Decimal::one());
+        assert_eq!(Decimal::percent(400).sqrt(), Decimal::percent(200));
+        assert_eq!(Decimal::percent(25).sqrt(), Decimal::percent(50));
+        assert_eq!(Decimal::percent(200).sqrt(), Decimal(1_414_213_562_373_095_048));
+        assert_eq!(Decimal::from_str("100000000").unwrap().sqrt(), Decimal::percent(1_000_000));
+        // large values keep fewer fractional digits
+        assert_eq!(Decimal::MAX.sqrt(), Decimal(18_446_744_073_709_551_615_000_000_000));
+    }
+
     #[test]
     fn decimal_to_string() {
         // Integers
@@ -747,6 +1016,76 @@ mod test {
         Uint128(500).multiply_ratio(1u128, 0u128);
     }
 
+    #[test]
+    fn u128_multiply_ratio_does_not_overflow_when_result_fits() {
+        let max = Uint128(u128::MAX);
+        assert_eq!(max.multiply_ratio(2u128, 2u128), max);
+        assert_eq!(max.multiply_ratio(u128::MAX, u128::MAX), max);
+        assert_eq!(max.multiply_ratio(1u128, 2u128), Uint128(u128::MAX / 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "multiply_ratio overflow")]
+    fn u128_multiply_ratio_panics_for_overflowing_result() {
+        Uint128(u128::MAX).multiply_ratio(2u128, 1u128);
+    }
+
+    #[test]
+    fn u128_checked_multiply_ratio_works() {
+        let base = Uint128(500);
+        assert_eq!(base.checked_multiply_ratio(3u128, 2u128), Some(Uint128(750)));
+        assert_eq!(base.checked_multiply_ratio(1u128, 0u128), None);
+        assert_eq!(Uint128(u128::MAX).checked_multiply_ratio(2u128, 2u128), Some(Uint128(u128::MAX)));
+        assert_eq!(Uint128(u128::MAX).checked_multiply_ratio(3u128, 2u128), None);
+
+        assert_eq!(base.checked_multiply_ratio_ceil(2u128, 3u128), Some(Uint128(334)));
+        assert_eq!(base.checked_multiply_ratio_ceil(3u128, 3u128), Some(Uint128(500)));
+        assert_eq!(base.checked_multiply_ratio_ceil(1u128, 0u128), None);
+        assert_eq!(Uint128(u128::MAX).checked_multiply_ratio_ceil(u128::MAX - 1, u128::MAX), Some(Uint128(u128::MAX - 1)));
+    }
+
+    #[test]
+    fn u128_checked_mul_decimal_rounds() {
+        let base = Uint128(15);
+        assert_eq!(base.checked_mul_floor(Decimal::percent(50)), Some(Uint128(7)));
+        assert_eq!(base.checked_mul_ceil(Decimal::percent(50)), Some(Uint128(8)));
+        assert_eq!(base.checked_mul_ceil(Decimal::percent(200)), Some(Uint128(30)));
+        assert_eq!(Uint128(u128::MAX).checked_mul_floor(Decimal::one()), Some(Uint128(u128::MAX)));
+        assert_eq!(Uint128(u128::MAX).checked_mul_ceil(Decimal::percent(101)), None);
+    }
+
+    #[test]
+    fn u128_checked_ops() {
+        let max = Uint128(u128::MAX);
+        assert_eq!(Uint128(2).checked_add(Uint128(3)), Some(Uint128(5)));
+        assert_eq!(max.checked_add(Uint128(1)), None);
+        assert_eq!(Uint128(5).checked_sub(Uint128(3)), Some(Uint128(2)));
+        assert_eq!(Uint128(3).checked_sub(Uint128(5)), None);
+        assert_eq!(Uint128(6).checked_mul(Uint128(7)), Some(Uint128(42)));
+        assert_eq!(max.checked_mul(Uint128(2)), None);
+        assert_eq!(Uint128(7).checked_div(Uint128(2)), Some(Uint128(3)));
+        assert_eq!(Uint128(7).checked_div(Uint128(0)), None);
+    }
+
+    #[test]
+    fn u256_full_mul_and_div() {
+        let max = u128::MAX;
+        assert_eq!(Uint256::full_mul(0, max), Uint256::from(0));
+        assert_eq!(Uint256::full_mul(6, 7), Uint256::from(42));
+        // (2^128 - 1)^2 == 2^256 - 2^129 + 1
+        assert_eq!(Uint256::full_mul(max, max), Uint256 { hi: max - 1, lo: 1 });
+        assert_eq!(Uint256::full_mul(1 << 64, 1 << 64), Uint256 { hi: 1, lo: 0 });
+
+        assert_eq!(Uint256::full_mul(max, max).div_rem(max), Some((Uint256::from(max), 0)));
+        assert_eq!(Uint256::full_mul(max, 3).div_rem(2), Some((Uint256 { hi: 1, lo: max / 2 - 1 }, 1)));
+        assert_eq!(Uint256::from(42).div_rem(0), None);
+        assert_eq!(Uint256::full_mul(max, max).checked_div_u128(2), None);
+        assert_eq!(Uint256::from(7).checked_div_ceil_u128(2), Some(4));
+        assert_eq!(Uint256::from(max).checked_div_ceil_u128(1), Some(max));
+
+        assert!(Uint256 { hi: 1, lo: 0 } > Uint256::from(max));
+    }
+
     #[test]
     // in this test the Decimal is on the left
     fn decimal_uint128_multiply() {
@@ -1,8 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CosmosMsg, Empty};
+use cosmwasm_std::{Binary, CosmosMsg, Empty, HumanAddr};
+use crate::deposit::ProposalDeposit;
 use crate::expiration::{Duration, Expiration};
+use crate::house::House;
+use crate::math::Uint128;
+use crate::nested::{ParentMultisig, ProposalAction};
+use crate::query::Status;
+use crate::rules::ThresholdRule;
+use crate::snapshot::StateSnapshot;
 use crate::threshold::Threshold;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -10,6 +17,42 @@ pub struct InitMsg {
     pub voters: Vec<Voter>,
     pub threshold: Threshold,
     pub max_voting_period: Duration,
+    /// Restricts proposing to these members. Any member (including zero weight ones)
+    /// may propose when unset.
+    pub proposers: Option<Vec<String>>,
+    /// Deposit to pay with each proposal, refunded or forfeited once the proposal
+    /// is executed or rejected
+    pub proposal_deposit: Option<ProposalDeposit>,
+    /// Tiered approvals: proposals with messages matching a rule need the strictest of
+    /// `threshold` and the matching rules' thresholds
+    pub threshold_rules: Option<Vec<ThresholdRule>>,
+    /// Addresses allowed to pause the multisig, they don't need to be members
+    pub guardians: Option<Vec<String>>,
+    /// Multi-house approval: every proposal must also reach each house's threshold
+    /// among the voters of that group
+    pub houses: Option<Vec<House>>,
+    /// Starts with an import phase, to load the state exported from a previous deployment
+    /// with `ImportState` before any proposal is made
+    pub import_state: Option<bool>,
+    /// Factory to register with once the multisig is instantiated, so that it
+    /// learns its address from the sender
+    pub callback: Option<InitCallback>,
+}
+
+/// The factory a multisig registers with at the end of `init`, under the `id` it was
+/// created with. No other message can be sent from `init`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InitCallback {
+    pub contract_addr: HumanAddr,
+    pub code_hash: String,
+    pub id: u64,
+}
+
+/// The factory's registration message, sent to `InitCallback::contract_addr`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FactoryCallbackMsg {
+    RegisterMultisig { id: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,17 +64,136 @@ pub enum HandleMsg {
         msgs: Vec<CosmosMsg<Empty>>,
         // note: we ignore API-spec'd earliest if passed, always opens immediately
         latest: Option<Expiration>,
+        /// once passed, the proposal can only be executed from this block or time on
+        execute_at: Option<Expiration>,
+        /// proposals that must be executed before this one. It is rejected if one of them is.
+        #[serde(default)]
+        depends_on: Vec<u64>,
+    },
+    /// Freezes proposing, voting and executing until `until` (forever by default).
+    /// Can only be called by guardians. Proposals that only unpause stay allowed.
+    Pause {
+        until: Option<Expiration>,
+    },
+    /// Lifts a pause. Can only be called by the multisig itself, through a proposal.
+    /// The guardian that paused cannot pause again until it is re-appointed.
+    Unpause {},
+    /// Replaces the guardians, re-appointing those whose pause was lifted.
+    /// Can only be called by the multisig itself, through a proposal
+    UpdateGuardians {
+        guardians: Vec<String>,
+    },
+    /// Loads a page of `ExportState` from a previous deployment with the same voters. Only
+    /// accepted from initial voters with voting power, while the import started with
+    /// `InitMsg.import_state` is open
+    ImportState {
+        snapshot: Box<StateSnapshot>,
+    },
+    /// Approves the state imported so far. The import closes, allowing new proposals, once
+    /// every initial voter with voting power approved it; importing another page resets
+    /// the approvals
+    FinishImport {},
+    /// Replaces the executed and rejected proposals before `before_id` by a summary,
+    /// deleting their messages and ballots. Can only be called by the multisig itself,
+    /// through a proposal
+    Prune {
+        before_id: u64,
+    },
+    /// Same as `Propose`, with the message built from a typed action
+    ProposeAction {
+        title: String,
+        description: String,
+        action: ProposalAction,
+        latest: Option<Expiration>,
+        /// once passed, the proposal can only be executed from this block or time on
+        execute_at: Option<Expiration>,
     },
     Vote {
         proposal_id: u64,
         vote: Vote,
     },
+    /// Casts each `(proposal_id, vote)` as `Vote` would. Returns a VoteBatchResponse in `data`.
+    VoteBatch {
+        votes: Vec<(u64, Vote)>,
+        #[serde(default)]
+        mode: VoteBatchMode,
+    },
+    /// Casts votes signed off-chain by members, see `SignDoc` for what is signed. Anyone can
+    /// relay them.
+    SubmitSignedVotes {
+        votes: Vec<SignedVote>,
+    },
+    /// Executes a passed proposal. `step` executes one message of a proposal made with the
+    /// `steps` action, without it every message not executed yet is sent.
     Execute {
         proposal_id: u64,
+        step: Option<u32>,
     },
+    /// Rejects an expired proposal that did not pass, or abandons the steps left of an
+    /// expired partially executed one
     Close {
         proposal_id: u64,
     },
+    /// Executes up to `limit` passed proposals whose `execute_at` was reached, earliest first,
+    /// starting after the queued proposal `start_after`. As a proposal whose messages fail
+    /// reverts the whole call, it can be skipped this way. Anyone can call it.
+    ExecuteDue {
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    /// Lets `to` cast the sender's weight along with its own until `until` (or until
+    /// undelegated). Votes the sender casts directly still take precedence.
+    Delegate {
+        to: String,
+        until: Option<Expiration>,
+    },
+    /// Takes back the sender's delegated voting power
+    Undelegate {},
+    /// Registers a contract to notify of proposal events.
+    /// Can only be called by the multisig itself, through a proposal
+    AddHook {
+        address: HumanAddr,
+        code_hash: String,
+    },
+    /// Stops notifying a contract. Can only be called by the multisig itself, through a
+    /// proposal. Hooks are not notified of proposals that only remove hooks, so a failing
+    /// hook cannot block its removal
+    RemoveHook {
+        address: HumanAddr,
+    },
+    /// SNIP-20 receiver interface, used to pay proposal deposits in tokens.
+    /// `msg` must be a `ReceiveMsg`. `sender` is the proposer, and `from`, the tokens' owner,
+    /// is refunded the deposit
+    Receive {
+        sender: HumanAddr,
+        from: HumanAddr,
+        amount: Uint128,
+        msg: Option<Binary>,
+    },
+}
+
+/// Messages that can be attached to a SNIP-20 `Send` to this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// Same as `HandleMsg::Propose`, the sent tokens pay the deposit
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<Empty>>,
+        latest: Option<Expiration>,
+        execute_at: Option<Expiration>,
+        #[serde(default)]
+        depends_on: Vec<u64>,
+    },
+    /// Same as `HandleMsg::ProposeAction`, the sent tokens pay the deposit
+    ProposeAction {
+        title: String,
+        description: String,
+        action: ProposalAction,
+        latest: Option<Expiration>,
+        execute_at: Option<Expiration>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -39,6 +201,10 @@ pub enum HandleMsg {
 pub enum QueryMsg {
     /// Return ThresholdResponse
     Threshold {},
+    /// Returns ConfigResponse
+    Config {},
+    /// Returns ContractInfoResponse
+    ContractInfo {},
     /// Returns ProposalResponse
     Proposal { proposal_id: u64 },
     /// Returns ProposalListResponse
@@ -66,12 +232,45 @@ pub enum QueryMsg {
         start_after: Option<String>, // Currently no use for this
         limit: Option<u32>,
     },
+    /// Returns DelegationResponse
+    Delegation { address: String },
+    /// Returns VotingPowerResponse, the weight `address` casts (or would cast) on a proposal
+    VotingPower { proposal_id: u64, address: String },
+    /// Returns DepositResponse
+    Deposit { proposal_id: u64 },
+    /// Returns ProposalListResponse with the open proposals `address` has not voted on yet and
+    /// can cast weight on, its own or delegated to it.
+    /// Queries have no block info, so proposals that expired since their last update are
+    /// still listed until closed; check `expires` on each entry.
+    PendingForVoter { address: String },
+    /// Returns HooksResponse with the contracts notified of proposal events
+    Hooks {},
+    /// Returns StateSnapshot with the proposals (and their ballots) after `start_after`,
+    /// to rebuild this multisig on a new code version. Pruned proposals are exported as
+    /// their summary
+    ExportState {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns SimulateExecuteResponse, previewing what executing `proposal_id` would send
+    SimulateExecute {
+        proposal_id: u64,
+    },
+    /// Returns the ProposalResponse of `proposal_id` on a parent multisig,
+    /// for members to see what this multisig votes on
+    ParentProposal {
+        parent: ParentMultisig,
+        proposal_id: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Voter {
     pub addr: String,
     pub weight: u64,
+    /// house the member votes in, see `InitMsg.houses`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
@@ -86,4 +285,43 @@ pub enum Vote {
     /// Veto is generally to be treated as a No vote. Some implementations may allow certain
     /// voters to be able to Veto, or them to be counted stronger than No in some way.
     Veto,
+}
+
+/// A vote signed by a member with its account key
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignedVote {
+    pub proposal_id: u64,
+    pub vote: Vote,
+    /// any number the member did not sign with before, so the signature can only be used once
+    pub nonce: u64,
+    /// compressed secp256k1 public key of the member
+    pub pubkey: Binary,
+    /// signature of the `SignDoc` hash, in compact 64 bytes form, as `signArbitrary` returns it
+    pub signature: Binary,
+}
+
+/// How `VoteBatch` handles a vote that fails
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteBatchMode {
+    /// the whole batch fails
+    #[default]
+    AllOrNothing,
+    /// the vote is skipped, and its error reported
+    BestEffort,
+}
+
+/// Data of `VoteBatch`, one result per vote in the order they were given
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoteBatchResponse {
+    pub results: Vec<VoteBatchResult>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoteBatchResult {
+    pub proposal_id: u64,
+    /// status of the proposal after the vote, None if it failed
+    pub status: Option<Status>,
+    /// why the vote failed, in best-effort mode
+    pub error: Option<String>,
 }
\ No newline at end of file
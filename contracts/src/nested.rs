@@ -0,0 +1,63 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    to_binary, CosmosMsg, HumanAddr, Querier, QueryRequest, StdResult, WasmMsg, WasmQuery,
+};
+
+use crate::msg::{HandleMsg, QueryMsg, Vote};
+use crate::query::ProposalResponse;
+
+/// Another multisig this one is a voter of
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ParentMultisig {
+    pub address: HumanAddr,
+    pub code_hash: String,
+}
+
+impl ParentMultisig {
+    /// queries the proposal `proposal_id` of the parent
+    pub fn query_proposal<Q: Querier>(&self, querier: &Q, proposal_id: u64) -> StdResult<ProposalResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.address.clone(),
+            callback_code_hash: self.code_hash.clone(),
+            msg: to_binary(&QueryMsg::Proposal { proposal_id })?,
+        }))
+    }
+}
+
+/// Typed actions a proposal can perform, lowered to plain messages when proposed
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalAction {
+    /// A signalling proposal, without messages, that only records the members' opinion
+    Signal {},
+    /// Messages executed one at a time with `Execute { proposal_id, step }`, so that one
+    /// failing message does not hold back the others
+    Steps { msgs: Vec<CosmosMsg> },
+    /// Casts this multisig's vote on a proposal of its parent
+    VoteOnParent {
+        parent: ParentMultisig,
+        proposal_id: u64,
+        vote: Vote,
+    },
+}
+
+impl ProposalAction {
+    pub fn into_cosmos_msgs(self) -> StdResult<Vec<CosmosMsg>> {
+        match self {
+            ProposalAction::Signal {} => Ok(vec![]),
+            ProposalAction::Steps { msgs } => Ok(msgs),
+            ProposalAction::VoteOnParent {
+                parent,
+                proposal_id,
+                vote,
+            } => Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: parent.address,
+                callback_code_hash: parent.code_hash,
+                msg: to_binary(&HandleMsg::Vote { proposal_id, vote })?,
+                send: vec![],
+            })]),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{from_binary, BlockInfo, CosmosMsg, Empty, HumanAddr, StdError, StdResult, Storage, WasmMsg};
+
+use crate::error::ContractError;
+use crate::expiration::Expiration;
+use crate::msg::HandleMsg;
+use crate::state::pause_read;
+
+/// Set by a guardian to freeze proposing, voting and executing
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Pause {
+    /// guardian that paused the multisig
+    pub guardian: String,
+    /// the pause lifts by itself at this point, if it is not unpaused before
+    pub until: Expiration,
+}
+
+impl Pause {
+    pub fn is_active(&self, block: &BlockInfo) -> bool {
+        !self.until.is_expired(block)
+    }
+}
+
+/// Returns true if all of `msgs` unpause the multisig at `contract`. Those proposals stay
+/// allowed while paused, as they are the only way to unpause early.
+pub fn is_unpause_proposal(msgs: &[CosmosMsg<Empty>], contract: &HumanAddr) -> bool {
+    !msgs.is_empty()
+        && msgs.iter().all(|msg| match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                contract_addr == contract
+                    && matches!(from_binary(msg), Ok(HandleMsg::Unpause {}))
+            }
+            _ => false,
+        })
+}
+
+/// Fails with `error` if the multisig is paused, unless `msgs` is an unpause proposal
+pub fn check_not_paused<S: Storage>(
+    storage: &S,
+    block: &BlockInfo,
+    msgs: &[CosmosMsg<Empty>],
+    contract: &HumanAddr,
+    error: ContractError,
+) -> StdResult<()> {
+    match pause_read(storage).may_load()? {
+        Some(pause) if pause.is_active(block) && !is_unpause_proposal(msgs, contract) => {
+            Err(StdError::generic_err(error.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
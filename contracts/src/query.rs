@@ -2,11 +2,18 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-use cosmwasm_std::{CosmosMsg, Empty};
+use cosmwasm_std::{Coin, CosmosMsg, Empty, HumanAddr};
 
+use crate::archive::ArchivedSummary;
+use crate::deposit::{Deposit, DepositInfo, ProposalDeposit};
+use crate::hooks::Hook;
+use crate::house::House;
 use crate::msg::{Vote, Voter};
-use crate::expiration::Expiration;
-use crate::threshold::ThresholdResponse;
+use crate::pause::Pause;
+use crate::rules::ThresholdRule;
+use crate::state::Delegation;
+use crate::expiration::{Duration, Expiration};
+use crate::threshold::{Threshold, ThresholdResponse};
 
 /// Note, if you are storing custom messages in the proposal,
 /// the querier needs to know what possible custom message types
@@ -26,6 +33,30 @@ where
     /// as well as the total_weight of the voting group may have changed since this time. That means
     /// that the generic `Threshold{}` query does not provide valid information for existing proposals.
     pub threshold: ThresholdResponse,
+    pub proposer: String,
+    /// deposit paid with the proposal, kept after it is refunded or forfeited
+    pub deposit: Option<DepositInfo>,
+    /// block height at which the proposal was created
+    #[serde(default)]
+    pub start_height: u64,
+    /// block time (in seconds) at which the proposal was created
+    #[serde(default)]
+    pub start_time: u64,
+    /// block or time from which the proposal can be executed, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execute_at: Option<Expiration>,
+    /// proposals that must be executed before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<u64>,
+    /// proposals that depend on this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependents: Vec<u64>,
+    /// status of each message of a proposal executed step by step, empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<StepStatus>,
+    /// set once the proposal is pruned, its title, description and msgs are then empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<ArchivedSummary>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
@@ -42,6 +73,43 @@ pub enum Status {
     Passed = 4,
     /// voting is over it passed, and the proposal was executed
     Executed = 5,
+    /// voting is over it passed, and some of its steps were executed
+    PartiallyExecuted = 6,
+}
+
+/// Status of one message of a proposal executed step by step
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Executed,
+}
+
+/// Every field of the stored `Config`, along with counters that are kept outside of it
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ConfigResponse {
+    pub threshold: Threshold,
+    pub total_weight: u64,
+    pub max_voting_period: Duration,
+    pub proposers: Option<Vec<String>>,
+    pub proposal_deposit: Option<ProposalDeposit>,
+    pub threshold_rules: Vec<ThresholdRule>,
+    pub houses: Vec<House>,
+    pub guardians: Vec<String>,
+    /// The pause in place, if any. Queries have no block info, so a pause that
+    /// expired is still returned until the next one replaces it; check `until`.
+    pub pause: Option<Pause>,
+    /// number of proposals created so far (also the id of the latest one)
+    pub proposal_count: u64,
+    /// number of members, including zero weight members
+    pub voter_count: u64,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ContractInfoResponse {
+    pub contract: String,
+    pub version: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -69,6 +137,71 @@ pub struct VoteResponse {
     pub vote: Option<VoteInfo>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DelegationResponse {
+    pub delegation: Option<Delegation>,
+}
+
+/// Weight a delegator adds to its delegate's vote
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DelegatedWeight {
+    pub delegator: String,
+    pub weight: u64,
+    pub until: Expiration,
+}
+
+/// Queries have no block info, so delegations that expired since are still listed
+/// for proposals the address has not voted on yet; check `until` on each entry.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VotingPowerResponse {
+    /// the address' own weight
+    pub weight: u64,
+    /// delegators whose weight is (or would be) cast by the address
+    pub delegated: Vec<DelegatedWeight>,
+    /// own plus delegated weight
+    pub total: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HooksResponse {
+    pub hooks: Vec<Hook>,
+}
+
+/// What executing a proposal would do right now
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SimulateExecuteResponse {
+    /// messages `Execute` would send, including the deposit refund and hook notifications
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub bank_sends: Vec<BankSendSummary>,
+    pub contract_calls: Vec<ContractCallSummary>,
+    /// native funds sent by `msgs`, per denom
+    pub total_sent: Vec<Coin>,
+    /// current native balance of the multisig
+    pub balance: Vec<Coin>,
+    /// true if `balance` covers `total_sent`
+    pub balance_covers: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BankSendSummary {
+    pub to_address: HumanAddr,
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ContractCallSummary {
+    pub contract_addr: HumanAddr,
+    /// the decoded JSON message
+    pub msg: String,
+    pub send: Vec<Coin>,
+}
+
+/// The deposit still held for a proposal, None if there was none or it was settled
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositResponse {
+    pub deposit: Option<Deposit>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct VoterResponse {
     pub weight: Option<u64>,
@@ -76,11 +209,88 @@ pub struct VoterResponse {
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct VoterListResponse {
-    pub voters: Vec<Voter>,
+    pub voters: Vec<VoterDetail>,
 }
 
+/// A member as listed by cw3's `list_voters`
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct VoterDetail {
     pub addr: String,
     pub weight: u64,
+    /// only set for members of a house
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl From<Voter> for VoterDetail {
+    fn from(voter: Voter) -> Self {
+        VoterDetail {
+            addr: voter.addr,
+            weight: voter.weight,
+            group: voter.group,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_slice, to_vec};
+    use serde::de::DeserializeOwned;
+
+    use crate::msg::QueryMsg;
+
+    /// parses a cw3 fixture and checks it serializes back to the same JSON
+    fn round_trip<T: Serialize + DeserializeOwned>(fixture: &str) -> T {
+        let parsed: T = from_slice(fixture.trim().as_bytes()).unwrap();
+        assert_eq!(String::from_utf8(to_vec(&parsed).unwrap()).unwrap(), fixture.trim());
+        parsed
+    }
+
+    #[test]
+    fn cw3_queries() {
+        let msg: QueryMsg = round_trip(include_str!("../testdata/cw3/vote_query.json"));
+        assert_eq!(
+            msg,
+            QueryMsg::Vote {
+                proposal_id: 1,
+                voter: "alice".to_string()
+            }
+        );
+        let msg: QueryMsg = round_trip(include_str!("../testdata/cw3/list_voters_query.json"));
+        assert_eq!(
+            msg,
+            QueryMsg::ListVoters {
+                start_after: Some("alice".to_string()),
+                limit: Some(30)
+            }
+        );
+    }
+
+    #[test]
+    fn cw3_responses() {
+        let res: VoteResponse = round_trip(include_str!("../testdata/cw3/vote_response.json"));
+        assert_eq!(res.vote.unwrap().voter, "alice");
+
+        // members outside of houses are listed exactly like cw3 voters
+        let res: VoterListResponse = round_trip(include_str!("../testdata/cw3/list_voters_response.json"));
+        assert_eq!(res.voters[1].addr, "bob");
+        assert_eq!(res.voters[1].group, None);
+    }
+
+    #[test]
+    fn cw3_proposal_response() {
+        let fixture = include_str!("../testdata/cw3/proposal_response.json").trim();
+        let res: ProposalResponse = from_slice(fixture.as_bytes()).unwrap();
+        assert_eq!(res.proposer, "alice");
+        assert_eq!(res.deposit.as_ref().unwrap().amount.u128(), 10);
+
+        // our own fields come after all of cw3's
+        let json = String::from_utf8(to_vec(&res).unwrap()).unwrap();
+        let expected = format!(
+            "{},\"start_height\":0,\"start_time\":0}}",
+            fixture.strip_suffix('}').unwrap()
+        );
+        assert_eq!(json, expected);
+    }
 }
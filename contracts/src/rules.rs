@@ -0,0 +1,182 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{BankMsg, CosmosMsg, Empty, HumanAddr, StdResult, WasmMsg};
+
+use crate::math::Uint128;
+use crate::threshold::Threshold;
+
+/// A kind of action a proposal message can perform, used to require a different
+/// threshold for it than the default one.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionCategory {
+    /// Bank sends. If `denom` is set, only sends including that denom match and the bounds
+    /// apply to its amount, otherwise they apply to the largest coin sent.
+    /// Both bounds are inclusive.
+    BankSend {
+        denom: Option<String>,
+        min_amount: Option<Uint128>,
+        max_amount: Option<Uint128>,
+    },
+    /// Executing any of these contracts, or any contract at all if empty
+    WasmExecute { contracts: Vec<HumanAddr> },
+    /// Messages sent to the multisig itself
+    SelfGovernance {},
+}
+
+impl ActionCategory {
+    /// returns true if `msg`, sent by the multisig at `contract`, falls in this category
+    pub fn matches(&self, msg: &CosmosMsg<Empty>, contract: &HumanAddr) -> bool {
+        match (self, msg) {
+            (
+                ActionCategory::BankSend {
+                    denom,
+                    min_amount,
+                    max_amount,
+                },
+                CosmosMsg::Bank(BankMsg::Send { amount, .. }),
+            ) => {
+                let sent = match denom {
+                    Some(denom) => match amount.iter().find(|c| &c.denom == denom) {
+                        Some(coin) => coin.amount.u128(),
+                        None => return false,
+                    },
+                    None => amount.iter().map(|c| c.amount.u128()).max().unwrap_or(0),
+                };
+                min_amount.map_or(true, |min| sent >= min.u128())
+                    && max_amount.map_or(true, |max| sent <= max.u128())
+            }
+            (
+                ActionCategory::WasmExecute { contracts },
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }),
+            ) => contracts.is_empty() || contracts.contains(contract_addr),
+            (ActionCategory::SelfGovernance {}, CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })) => {
+                contract_addr == contract
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Requires at least `threshold` for proposals containing a message in `category`
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ThresholdRule {
+    pub category: ActionCategory,
+    pub threshold: Threshold,
+}
+
+/// Validates every rule's threshold against the total weight of the multisig
+pub fn validate_rules(rules: &[ThresholdRule], total_weight: u64) -> StdResult<()> {
+    for rule in rules {
+        rule.threshold.validate(total_weight)?;
+    }
+    Ok(())
+}
+
+/// Picks the threshold a proposal with `msgs` must reach: the strictest of `default` and of
+/// the rules matched by any of its messages, compared by the yes weight each one requires
+/// when every member votes. Rules can only make a proposal harder to pass, never easier.
+pub fn select_threshold(
+    rules: &[ThresholdRule],
+    default: &Threshold,
+    msgs: &[CosmosMsg<Empty>],
+    contract: &HumanAddr,
+    total_weight: u64,
+) -> StdResult<Threshold> {
+    let mut selected = default;
+    let matching = rules
+        .iter()
+        .filter(|rule| msgs.iter().any(|msg| rule.category.matches(msg, contract)));
+    for rule in matching {
+        if rule.threshold.required_weight(total_weight)? > selected.required_weight(total_weight)? {
+            selected = &rule.threshold;
+        }
+    }
+    Ok(selected.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Binary};
+
+    const CONTRACT: &str = "multisig";
+
+    fn send(amount: u128, denom: &str) -> CosmosMsg<Empty> {
+        CosmosMsg::Bank(BankMsg::Send {
+            from_address: CONTRACT.into(),
+            to_address: "somebody".into(),
+            amount: vec![coin(amount, denom)],
+        })
+    }
+
+    fn execute(contract: &str) -> CosmosMsg<Empty> {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.into(),
+            callback_code_hash: "hash".to_string(),
+            msg: Binary::from(b"{}".to_vec()),
+            send: vec![],
+        })
+    }
+
+    #[test]
+    fn categories_match() {
+        let contract = HumanAddr::from(CONTRACT);
+        let large_sends = ActionCategory::BankSend {
+            denom: Some("uscrt".to_string()),
+            min_amount: Some(Uint128(1000)),
+            max_amount: None,
+        };
+        assert!(large_sends.matches(&send(1000, "uscrt"), &contract));
+        assert!(!large_sends.matches(&send(999, "uscrt"), &contract));
+        assert!(!large_sends.matches(&send(5000, "BTC"), &contract));
+        assert!(!large_sends.matches(&execute("dex"), &contract));
+
+        let dex = ActionCategory::WasmExecute {
+            contracts: vec![HumanAddr::from("dex")],
+        };
+        assert!(dex.matches(&execute("dex"), &contract));
+        assert!(!dex.matches(&execute("other"), &contract));
+
+        let governance = ActionCategory::SelfGovernance {};
+        assert!(governance.matches(&execute(CONTRACT), &contract));
+        assert!(!governance.matches(&execute("dex"), &contract));
+    }
+
+    #[test]
+    fn strictest_threshold_is_selected() {
+        let contract = HumanAddr::from(CONTRACT);
+        let default = Threshold::AbsoluteCount { weight: 2 };
+        let rules = vec![
+            ThresholdRule {
+                category: ActionCategory::BankSend {
+                    denom: None,
+                    min_amount: None,
+                    max_amount: Some(Uint128(100)),
+                },
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+            },
+            ThresholdRule {
+                category: ActionCategory::SelfGovernance {},
+                threshold: Threshold::AbsoluteCount { weight: 4 },
+            },
+        ];
+
+        // no messages and unmatched messages use the default
+        assert_eq!(select_threshold(&rules, &default, &[], &contract, 5).unwrap(), default);
+        let msgs = vec![execute("dex")];
+        assert_eq!(select_threshold(&rules, &default, &msgs, &contract, 5).unwrap(), default);
+
+        // a matching rule looser than the default doesn't lower it
+        let msgs = vec![send(10, "uscrt")];
+        assert_eq!(select_threshold(&rules, &default, &msgs, &contract, 5).unwrap(), default);
+
+        // the strictest matching rule wins
+        let msgs = vec![send(10, "uscrt"), execute("dex"), execute(CONTRACT)];
+        assert_eq!(
+            select_threshold(&rules, &default, &msgs, &contract, 5).unwrap(),
+            Threshold::AbsoluteCount { weight: 4 }
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_vec, Api, Binary, CanonicalAddr, Coin, HumanAddr, StdError, StdResult};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{SignedVote, Vote};
+
+/// The vote a member signs, binding it to this chain, multisig and nonce. Its compact JSON,
+/// with the fields in this order, is the `data` of the `SignDoc`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoteData {
+    pub chain_id: String,
+    /// the multisig
+    pub contract: HumanAddr,
+    pub proposal_id: u64,
+    pub vote: Vote,
+    pub nonce: u64,
+}
+
+/// What a member signs to vote without broadcasting a transaction: the sha256 hash of this
+/// document as compact JSON. This is the ADR-036 document Keplr's `signArbitrary` and the
+/// Ledger Cosmos app sign, an amino `StdSignDoc` whose only message is a `sign/MsgSignData`
+/// and whose chain id, account number, sequence and fee are empty. Fields are declared in
+/// alphabetical order, as amino JSON sorts them.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignDoc {
+    pub account_number: String,
+    pub chain_id: String,
+    pub fee: SignDocFee,
+    pub memo: String,
+    pub msgs: Vec<SignDocMsg>,
+    pub sequence: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignDocFee {
+    pub amount: Vec<Coin>,
+    pub gas: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignDocMsg {
+    /// always `sign/MsgSignData`
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub value: MsgSignData,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MsgSignData {
+    /// the `VoteData` JSON
+    pub data: Binary,
+    /// address of the member
+    pub signer: HumanAddr,
+}
+
+impl SignDoc {
+    pub fn new(chain_id: &str, contract: &HumanAddr, signer: &HumanAddr, signed: &SignedVote) -> StdResult<Self> {
+        let data = VoteData {
+            chain_id: chain_id.to_string(),
+            contract: contract.clone(),
+            proposal_id: signed.proposal_id,
+            vote: signed.vote,
+            nonce: signed.nonce,
+        };
+        Ok(SignDoc {
+            account_number: "0".to_string(),
+            chain_id: String::new(),
+            fee: SignDocFee { amount: vec![], gas: "0".to_string() },
+            memo: String::new(),
+            msgs: vec![SignDocMsg {
+                msg_type: "sign/MsgSignData".to_string(),
+                value: MsgSignData { data: Binary(to_vec(&data)?), signer: signer.clone() },
+            }],
+            sequence: "0".to_string(),
+        })
+    }
+
+    pub fn hash(&self) -> StdResult<Vec<u8>> {
+        Ok(Sha256::digest(&to_vec(self)?).to_vec())
+    }
+}
+
+/// Address of the account of a compressed secp256k1 `pubkey`, derived as the chain does
+pub fn pubkey_address<A: Api>(api: &A, pubkey: &[u8]) -> StdResult<HumanAddr> {
+    if pubkey.len() != 33 {
+        return Err(error(ContractError::InvalidPubkey {}));
+    }
+    let hash = Ripemd160::digest(&Sha256::digest(pubkey));
+    api.human_address(&CanonicalAddr(Binary(hash.to_vec())))
+        .map_err(|_| error(ContractError::InvalidPubkey {}))
+}
+
+/// Checks the signature of `signed` on this chain and multisig, returning the signer
+pub fn verify_signed_vote<A: Api>(
+    api: &A,
+    chain_id: &str,
+    contract: &HumanAddr,
+    signed: &SignedVote,
+) -> StdResult<HumanAddr> {
+    check_signed_vote(api, chain_id, contract, signed, |hash, signature, pubkey| {
+        matches!(api.secp256k1_verify(hash, signature, pubkey), Ok(true))
+    })
+}
+
+/// `verify_signed_vote` with the secp256k1 check `verify(hash, signature, pubkey)`.
+/// `MockApi` accepts any signature, and this cosmwasm version keeps the `Api` error types
+/// private so it cannot be wrapped: tests pass their own check here.
+fn check_signed_vote<A: Api, F: Fn(&[u8], &[u8], &[u8]) -> bool>(
+    api: &A,
+    chain_id: &str,
+    contract: &HumanAddr,
+    signed: &SignedVote,
+    verify: F,
+) -> StdResult<HumanAddr> {
+    let signer = pubkey_address(api, signed.pubkey.as_slice())?;
+    let hash = SignDoc::new(chain_id, contract, &signer, signed)?.hash()?;
+    if !verify(&hash, signed.signature.as_slice(), signed.pubkey.as_slice()) {
+        return Err(error(ContractError::InvalidSignature {}));
+    }
+    Ok(signer)
+}
+
+fn error(err: ContractError) -> StdError {
+    StdError::generic_err(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockApi;
+    use k256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+
+    use super::*;
+
+    #[test]
+    fn sign_doc_is_canonical() {
+        let signed = SignedVote {
+            proposal_id: 3,
+            vote: Vote::Yes,
+            nonce: 7,
+            pubkey: Binary(vec![2; 33]),
+            signature: Binary(vec![0; 64]),
+        };
+        let doc = SignDoc::new("secret-4", &HumanAddr::from("multisig"), &HumanAddr::from("member"), &signed).unwrap();
+        // what Keplr's signArbitrary signs for "member", with the vote JSON as data:
+        // {"chain_id":"secret-4","contract":"multisig","proposal_id":3,"vote":"yes","nonce":7}
+        assert_eq!(
+            String::from_utf8(to_vec(&doc).unwrap()).unwrap(),
+            concat!(
+                r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","#,
+                r#""msgs":[{"type":"sign/MsgSignData","value":{"data":"#,
+                r#""eyJjaGFpbl9pZCI6InNlY3JldC00IiwiY29udHJhY3QiOiJtdWx0aXNpZyIsInByb3Bvc2FsX2lkIjozLCJ2b3RlIjoieWVzIiwibm9uY2UiOjd9","#,
+                r#""signer":"member"}}],"sequence":"0"}"#,
+            )
+        );
+        assert_eq!(doc.hash().unwrap().len(), 32);
+    }
+
+    #[test]
+    fn pubkey_address_works() {
+        // ripemd160(sha256(pubkey)) happens to be printable, so the mock can show it
+        let mut pubkey = vec![0; 33];
+        pubkey[0] = 2;
+        pubkey[30..].copy_from_slice(&[0xfe, 0xb1, 0xaf]);
+        let api = MockApi::new(20);
+        assert_eq!(pubkey_address(&api, &pubkey).unwrap(), HumanAddr::from("d<nm3IfQ[d|ZoPlwU&d="));
+
+        let err = pubkey_address(&api, &pubkey[1..]).unwrap_err();
+        assert_eq!(err, error(ContractError::InvalidPubkey {}));
+    }
+
+    /// secp256k1 check of the chain, on the hash, compact signature and compressed pubkey
+    fn k256_verify(hash: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
+        match (VerifyingKey::from_sec1_bytes(pubkey), Signature::from_slice(signature)) {
+            (Ok(key), Ok(signature)) => key.verify_prehash(hash, &signature).is_ok(),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn signature_binds_the_vote() {
+        let api = MockApi::new(20);
+        // a key whose address the mock api can represent
+        let mut secret = [0; 32];
+        secret[30..].copy_from_slice(&4182u16.to_be_bytes());
+        let key = SigningKey::from_slice(&secret).unwrap();
+        let pubkey = key.verifying_key().to_encoded_point(true).as_bytes().to_vec();
+        let member = pubkey_address(&api, &pubkey).unwrap();
+        let contract = HumanAddr::from("multisig");
+
+        let sign = |chain_id: &str, contract: &HumanAddr, vote: Vote, nonce: u64| {
+            let mut signed = SignedVote {
+                proposal_id: 1,
+                vote,
+                nonce,
+                pubkey: Binary(pubkey.clone()),
+                signature: Binary(vec![]),
+            };
+            let hash = SignDoc::new(chain_id, contract, &member, &signed).unwrap().hash().unwrap();
+            let signature: Signature = key.sign_prehash(&hash).unwrap();
+            signed.signature = Binary(signature.to_bytes().to_vec());
+            signed
+        };
+        let check = |signed: &SignedVote| check_signed_vote(&api, "secret-4", &contract, signed, k256_verify);
+
+        let signed = sign("secret-4", &contract, Vote::Yes, 1);
+        assert_eq!(check(&signed).unwrap(), member);
+
+        // the signature only holds for this chain, multisig, vote and nonce
+        let mut wrong_vote = signed.clone();
+        wrong_vote.vote = Vote::No;
+        let mut wrong_nonce = signed.clone();
+        wrong_nonce.nonce = 2;
+        let mut wrong_signature = signed;
+        wrong_signature.signature = Binary(vec![1; 64]);
+        let invalid = [
+            sign("pulsar-3", &contract, Vote::Yes, 1),
+            sign("secret-4", &HumanAddr::from("other"), Vote::Yes, 1),
+            wrong_vote,
+            wrong_nonce,
+            wrong_signature,
+        ];
+        for signed in invalid.iter() {
+            assert_eq!(check(signed).unwrap_err(), error(ContractError::InvalidSignature {}));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use cosmwasm_std::{BankMsg, Coin, CosmosMsg, HumanAddr, StdError, StdResult, Storage, Uint128, WasmMsg};
+
+use crate::error::ContractError;
+
+use crate::hooks::status_change_msgs;
+use crate::query::{BankSendSummary, ContractCallSummary, SimulateExecuteResponse, Status};
+use crate::state::{deposits_read, Proposal};
+
+/// Messages `Execute` sends for `steps` of `prop`: their own, followed by the deposit refund
+/// once the proposal is completed, and the hook notifications
+pub fn execution_msgs<S: Storage>(
+    storage: &S,
+    contract: &HumanAddr,
+    proposal_id: u64,
+    prop: &Proposal,
+    steps: &[usize],
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages: Vec<CosmosMsg> = steps.iter().map(|step| prop.msgs[*step].clone()).collect();
+    let mut executed = prop.clone();
+    executed.mark_executed(steps);
+    if executed.status == Status::Executed {
+        if let Some(deposit) = deposits_read(storage).may_load(&proposal_id.to_le_bytes())? {
+            if deposit.refund_policy.should_refund(Status::Executed, &prop.votes) {
+                messages.push(deposit.refund_msg(contract)?);
+            }
+        }
+    }
+    messages.extend(status_change_msgs(storage, contract, proposal_id, &executed, prop.status)?);
+    Ok(messages)
+}
+
+/// Describes what `msgs` do, and whether `balance` covers the native funds they send
+pub fn simulate(msgs: Vec<CosmosMsg>, balance: Vec<Coin>) -> StdResult<SimulateExecuteResponse> {
+    let mut bank_sends = vec![];
+    let mut contract_calls = vec![];
+    let mut total_sent: Vec<Coin> = vec![];
+    for msg in msgs.iter() {
+        match msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount, .. }) => {
+                add_coins(&mut total_sent, amount)?;
+                bank_sends.push(BankSendSummary {
+                    to_address: to_address.clone(),
+                    amount: amount.clone(),
+                });
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, send, .. }) => {
+                add_coins(&mut total_sent, send)?;
+                contract_calls.push(ContractCallSummary {
+                    contract_addr: contract_addr.clone(),
+                    // messages are JSON, anything else is shown in base64
+                    msg: String::from_utf8(msg.0.clone()).unwrap_or_else(|_| msg.to_base64()),
+                    send: send.clone(),
+                });
+            }
+            CosmosMsg::Wasm(WasmMsg::Instantiate { send, .. }) => add_coins(&mut total_sent, send)?,
+            _ => {}
+        }
+    }
+
+    let balance_covers = total_sent.iter().all(|sent| {
+        balance
+            .iter()
+            .any(|held| held.denom == sent.denom && held.amount.u128() >= sent.amount.u128())
+    });
+    Ok(SimulateExecuteResponse {
+        msgs,
+        bank_sends,
+        contract_calls,
+        total_sent,
+        balance,
+        balance_covers,
+    })
+}
+
+/// adds `coins` to `total`, merging coins of the same denom
+fn add_coins(total: &mut Vec<Coin>, coins: &[Coin]) -> StdResult<()> {
+    for coin in coins {
+        match total.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => {
+                let sum = existing.amount.u128().checked_add(coin.amount.u128())
+                    .ok_or_else(|| StdError::generic_err(ContractError::AmountOverflow {}.to_string()))?;
+                existing.amount = Uint128(sum);
+            }
+            None => total.push(coin.clone()),
+        }
+    }
+    Ok(())
+}
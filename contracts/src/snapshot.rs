@@ -0,0 +1,66 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::archive::ArchivedProposal;
+use crate::deposit::Deposit;
+use crate::msg::Voter;
+use crate::state::{Ballot, Config, Proposal};
+
+/// version of the snapshot format, bumped whenever its layout changes
+///
+/// 1. proposals only
+/// 2. pruned proposals are exported as their archived summary
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// A page of the contract state, as returned by `ExportState` and loaded by `ImportState`.
+/// Every page carries the config and voters; proposals are paged by id.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StateSnapshot {
+    pub version: u32,
+    /// version of the contract that exported it
+    pub contract_version: String,
+    pub config: Config,
+    pub voters: Vec<Voter>,
+    /// number of proposals in the exporting contract
+    pub proposal_count: u64,
+    pub proposals: Vec<ProposalSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ProposalSnapshot {
+    pub id: u64,
+    /// unset for pruned proposals, which only have their `archived` summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal: Option<Proposal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<ArchivedProposal>,
+    /// deposit paid with the proposal, kept as history once imported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposit: Option<Deposit>,
+    pub ballots: Vec<BallotSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BallotSnapshot {
+    pub voter: String,
+    pub ballot: Ballot,
+}
+
+/// Progress of a state import. Imported proposals are kept as read-only history: they
+/// cannot be voted on or executed, as the funds they spent belong to the old contract.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ImportStatus {
+    /// true until every initial voter with voting power calls `FinishImport`
+    pub importing: bool,
+    /// highest proposal id imported
+    pub last_imported_id: u64,
+    /// initial voters that approved the state imported so far, cleared by each new page
+    #[serde(default)]
+    pub approvals: Vec<String>,
+}
+
+impl ImportStatus {
+    pub fn is_imported(&self, proposal_id: u64) -> bool {
+        proposal_id <= self.last_imported_id
+    }
+}
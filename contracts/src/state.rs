@@ -1,15 +1,27 @@
+// the accessors keep the elided lifetimes of the original code
+#![allow(unknown_lints, mismatched_lifetime_syntaxes)]
+
 use schemars::JsonSchema;
 use serde::{ Deserialize, Serialize };
-use cosmwasm_std::{ BlockInfo, Storage, CosmosMsg, Empty };
+use cosmwasm_std::{ BlockInfo, Storage, CosmosMsg, Empty, HumanAddr, StdError, StdResult };
 use crate::math::{ Decimal, Uint128 };
 
 use cosmwasm_storage::{ Singleton, ReadonlySingleton, Bucket, ReadonlyBucket, 
     singleton, singleton_read, bucket, bucket_read };
 
-use crate::expiration::Duration;
+use crate::archive::ArchivedProposal;
+use crate::deposit::{ Deposit, ProposalDeposit };
+use crate::expiration::{ Duration, Expiration };
 use crate::msg::{ Voter, Vote };
-use crate::query::Status;
-use crate::threshold::Threshold;
+use crate::pause::Pause;
+use crate::error::ContractError;
+use crate::query::{ Status, StepStatus };
+use crate::rules::ThresholdRule;
+use crate::snapshot::ImportStatus;
+use crate::hooks::Hook;
+use crate::house::{ House, HouseTally, HouseThresholdResponse };
+use crate::threshold::{ Threshold, ThresholdResponse };
+use crate::upgrade::{ open_proposal_ids, ProposalBucket, ReadonlyProposalBucket };
 
 pub static CONFIG_KEY: &[u8] = b"config";
 pub static PROPOSAL_COUNT_KEY: &[u8] = b"proposal_count";
@@ -17,16 +29,44 @@ pub static BALLOTS_KEY: &[u8] = b"votes";
 pub static PROPOSALS_KEY: &[u8] = b"proposals";
 pub static VOTERS_KEY: &[u8] = b"voters";
 pub static VOTERS_LIST_KEY: &[u8] = b"list_of_voters";
+pub static OPEN_PROPOSALS_KEY: &[u8] = b"open_proposals";
+pub static DEPOSITS_KEY: &[u8] = b"deposits";
+pub static DELEGATIONS_KEY: &[u8] = b"delegations";
+pub static DELEGATORS_KEY: &[u8] = b"delegators";
+pub static DELEGATED_VOTES_KEY: &[u8] = b"delegated_votes";
+pub static VOTER_GROUPS_KEY: &[u8] = b"voter_groups";
+pub static HOOKS_KEY: &[u8] = b"hooks";
+pub static PAUSE_KEY: &[u8] = b"pause";
+pub static OVERRULED_GUARDIANS_KEY: &[u8] = b"overruled_guardians";
+pub static IMPORT_STATUS_KEY: &[u8] = b"import_status";
+pub static SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+pub static ARCHIVED_PROPOSALS_KEY: &[u8] = b"archived_proposals";
+pub static CONTRACT_ADDRESS_KEY: &[u8] = b"contract_address";
+pub static DUE_QUEUE_KEY: &[u8] = b"due_queue";
+pub static DEPENDENTS_KEY: &[u8] = b"dependents";
+pub static VOTE_NONCES_KEY: &[u8] = b"vote_nonces";
+pub static PRUNE_START_KEY: &[u8] = b"prune_start";
 
 pub const PREFIX_PROPOSAL_KEY: &[u8] = b"prefix";
 
-const PRECISION_FACTOR: u128 = 1_000_000_000;
-
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub threshold: Threshold,
     pub total_weight: u64,
     pub max_voting_period: Duration,
+    /// members allowed to propose, any member may propose when None
+    pub proposers: Option<Vec<String>>,
+    /// deposit required to create a proposal, if any
+    pub proposal_deposit: Option<ProposalDeposit>,
+    /// thresholds replacing `threshold` for proposals with specific kinds of messages
+    #[serde(default)]
+    pub threshold_rules: Vec<ThresholdRule>,
+    /// houses that must each approve proposals
+    #[serde(default)]
+    pub houses: Vec<House>,
+    /// addresses allowed to pause the multisig
+    #[serde(default)]
+    pub guardians: Vec<String>,
 }
 
 // Init configuration Read/Write functions
@@ -57,12 +97,33 @@ pub fn ballots_read<S: Storage>(storage: &S, id: u64) -> ReadonlyBucket<S, Ballo
 }
 
 // Proposals Read/Write functions
-pub fn proposals<S: Storage>(storage: &mut S) -> Bucket<S, Proposal> {
-    bucket(PROPOSALS_KEY, storage)
+// (proposals stored in an older layout are upgraded when loaded)
+pub fn proposals<S: Storage>(storage: &mut S) -> ProposalBucket<S> {
+    ProposalBucket::new(storage)
+}
+
+pub fn proposals_read<S: Storage>(storage: &S) -> ReadonlyProposalBucket<S> {
+    ReadonlyProposalBucket::new(storage)
+}
+
+// Archived proposals Read/Write functions
+// (summaries of pruned proposals, which are removed from the proposals bucket)
+pub fn archived_proposals<S: Storage>(storage: &mut S) -> Bucket<S, ArchivedProposal> {
+    bucket(ARCHIVED_PROPOSALS_KEY, storage)
+}
+
+pub fn archived_proposals_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, ArchivedProposal> {
+    bucket_read(ARCHIVED_PROPOSALS_KEY, storage)
+}
+
+// Prune start Read/Write functions
+// (lowest proposal id that may still be pruned, every one before it already was)
+pub fn prune_start<S: Storage>(storage: &mut S) -> Singleton<S, u64> {
+    singleton(storage, PRUNE_START_KEY)
 }
 
-pub fn proposals_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, Proposal> {
-    bucket_read(PROPOSALS_KEY, storage)
+pub fn prune_start_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, u64> {
+    singleton_read(storage, PRUNE_START_KEY)
 }
 
 // Voters Read/Write functions
@@ -75,6 +136,16 @@ pub fn voters_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, u64> {
     bucket_read(VOTERS_KEY, storage)
 }
 
+// Voter groups Read/Write functions
+// (member address -> group, only for members that have one)
+pub fn voter_groups<S: Storage>(storage: &mut S) -> Bucket<S, String> {
+    bucket(VOTER_GROUPS_KEY, storage)
+}
+
+pub fn voter_groups_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, String> {
+    bucket_read(VOTER_GROUPS_KEY, storage)
+}
+
 pub fn voters_list<S: Storage>(storage: &mut S) -> Singleton<S, Vec<Voter>> {
     singleton(storage, VOTERS_LIST_KEY)
 }
@@ -83,11 +154,245 @@ pub fn voters_list_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, Vec<Vot
     singleton_read(storage, VOTERS_LIST_KEY)
 }
 
+// Open proposals index Read/Write functions
+// (ids of proposals whose stored status is still Open, in creation order)
+pub fn open_proposals<S: Storage>(storage: &mut S) -> Singleton<S, Vec<u64>> {
+    singleton(storage, OPEN_PROPOSALS_KEY)
+}
+
+pub fn open_proposals_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, Vec<u64>> {
+    singleton_read(storage, OPEN_PROPOSALS_KEY)
+}
+
+/// adds a proposal id to the open proposals index
+pub fn add_open_proposal<S: Storage>(storage: &mut S, id: u64) -> StdResult<()> {
+    let mut open = open_proposal_ids(storage)?;
+    open.push(id);
+    open_proposals(storage).save(&open)
+}
+
+/// removes a proposal id from the open proposals index, if present
+pub fn remove_open_proposal<S: Storage>(storage: &mut S, id: u64) -> StdResult<()> {
+    let mut open = open_proposal_ids(storage)?;
+    open.retain(|open_id| *open_id != id);
+    open_proposals(storage).save(&open)
+}
+
+// Due queue Read/Write functions
+// (proposals scheduled with `execute_at`, earliest first: heights, then times)
+pub fn due_queue<S: Storage>(storage: &mut S) -> Singleton<S, Vec<DueProposal>> {
+    singleton(storage, DUE_QUEUE_KEY)
+}
+
+pub fn due_queue_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, Vec<DueProposal>> {
+    singleton_read(storage, DUE_QUEUE_KEY)
+}
+
+/// adds a proposal to the due queue, after those due at the same point
+pub fn add_due_proposal<S: Storage>(storage: &mut S, proposal_id: u64, execute_at: Expiration) -> StdResult<()> {
+    let mut queue = due_queue_read(storage).may_load()?.unwrap_or_default();
+    let order = due_order(&execute_at);
+    let position = queue
+        .iter()
+        .position(|due| due_order(&due.execute_at) > order)
+        .unwrap_or(queue.len());
+    queue.insert(position, DueProposal { proposal_id, execute_at });
+    due_queue(storage).save(&queue)
+}
+
+/// removes a proposal from the due queue, if present
+pub fn remove_due_proposal<S: Storage>(storage: &mut S, proposal_id: u64) -> StdResult<()> {
+    let mut queue = due_queue_read(storage).may_load()?.unwrap_or_default();
+    queue.retain(|due| due.proposal_id != proposal_id);
+    due_queue(storage).save(&queue)
+}
+
+// heights and times cannot be compared, so heights come first
+fn due_order(execute_at: &Expiration) -> (u8, u64) {
+    match execute_at {
+        Expiration::AtHeight(height) => (0, *height),
+        Expiration::AtTime(time) => (1, *time),
+        Expiration::Never {} => (2, 0),
+    }
+}
+
+// Dependents Read/Write functions
+// (ids of the proposals depending on a proposal, the reverse of `Proposal.depends_on`)
+pub fn dependents<S: Storage>(storage: &mut S) -> Bucket<S, Vec<u64>> {
+    bucket(DEPENDENTS_KEY, storage)
+}
+
+pub fn dependents_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, Vec<u64>> {
+    bucket_read(DEPENDENTS_KEY, storage)
+}
+
+/// records that `dependent` depends on `proposal_id`
+pub fn add_dependent<S: Storage>(storage: &mut S, proposal_id: u64, dependent: u64) -> StdResult<()> {
+    let key = proposal_id.to_le_bytes();
+    let mut ids = dependents_read(storage).may_load(&key)?.unwrap_or_default();
+    ids.push(dependent);
+    dependents(storage).save(&key, &ids)
+}
+
+// Vote nonces Read/Write functions
+// (nonces of the signed votes submitted, keyed by member key followed by the nonce)
+pub fn vote_nonces<S: Storage>(storage: &mut S) -> Bucket<S, bool> {
+    bucket(VOTE_NONCES_KEY, storage)
+}
+
+pub fn vote_nonces_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, bool> {
+    bucket_read(VOTE_NONCES_KEY, storage)
+}
+
+// Deposits Read/Write functions
+// (deposits paid with proposals, kept after they are settled until the proposal is pruned)
+pub fn deposits<S: Storage>(storage: &mut S) -> Bucket<S, Deposit> {
+    bucket(DEPOSITS_KEY, storage)
+}
+
+pub fn deposits_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, Deposit> {
+    bucket_read(DEPOSITS_KEY, storage)
+}
+
+// Hooks Read/Write functions
+// (contracts notified of proposal events)
+pub fn hooks<S: Storage>(storage: &mut S) -> Singleton<S, Vec<Hook>> {
+    singleton(storage, HOOKS_KEY)
+}
+
+pub fn hooks_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, Vec<Hook>> {
+    singleton_read(storage, HOOKS_KEY)
+}
+
+// Pause Read/Write functions
+// (only set while a guardian's pause is in place)
+pub fn pause<S: Storage>(storage: &mut S) -> Singleton<S, Pause> {
+    singleton(storage, PAUSE_KEY)
+}
+
+pub fn pause_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, Pause> {
+    singleton_read(storage, PAUSE_KEY)
+}
+
+// Overruled guardians Read/Write functions
+// (guardians whose pause was lifted by `Unpause`, until `UpdateGuardians` re-appoints them)
+pub fn overruled_guardians<S: Storage>(storage: &mut S) -> Singleton<S, Vec<String>> {
+    singleton(storage, OVERRULED_GUARDIANS_KEY)
+}
+
+pub fn overruled_guardians_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, Vec<String>> {
+    singleton_read(storage, OVERRULED_GUARDIANS_KEY)
+}
+
+// Import status Read/Write functions
+pub fn import_status<S: Storage>(storage: &mut S) -> Singleton<S, ImportStatus> {
+    singleton(storage, IMPORT_STATUS_KEY)
+}
+
+pub fn import_status_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, ImportStatus> {
+    singleton_read(storage, IMPORT_STATUS_KEY)
+}
+
+// Contract address Read/Write functions
+// (queries have no env, so the address is saved at init)
+pub fn contract_address<S: Storage>(storage: &mut S) -> Singleton<S, HumanAddr> {
+    singleton(storage, CONTRACT_ADDRESS_KEY)
+}
+
+pub fn contract_address_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, HumanAddr> {
+    singleton_read(storage, CONTRACT_ADDRESS_KEY)
+}
+
+// Schema version Read/Write functions
+// (layout version the contract was deployed with, missing for v0.1)
+pub fn schema_version<S: Storage>(storage: &mut S) -> Singleton<S, u32> {
+    singleton(storage, SCHEMA_VERSION_KEY)
+}
+
+pub fn schema_version_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, u32> {
+    singleton_read(storage, SCHEMA_VERSION_KEY)
+}
+
+// Delegations Read/Write functions
+// (delegator address -> the delegation it granted)
+pub fn delegations<S: Storage>(storage: &mut S) -> Bucket<S, Delegation> {
+    bucket(DELEGATIONS_KEY, storage)
+}
+
+pub fn delegations_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, Delegation> {
+    bucket_read(DELEGATIONS_KEY, storage)
+}
+
+// Delegators Read/Write functions
+// (delegate address -> addresses that delegated to it)
+pub fn delegators<S: Storage>(storage: &mut S) -> Bucket<S, Vec<String>> {
+    bucket(DELEGATORS_KEY, storage)
+}
+
+pub fn delegators_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, Vec<String>> {
+    bucket_read(DELEGATORS_KEY, storage)
+}
+
+// Delegated votes Read/Write functions
+// (delegator address -> weight a delegate cast for it on proposal `id`)
+pub fn delegated_votes<S: Storage>(storage: &mut S, id: u64) -> Bucket<S, DelegatedVote> {
+    Bucket::multilevel(&[DELEGATED_VOTES_KEY, &id.to_le_bytes()], storage)
+}
+
+pub fn delegated_votes_read<S: Storage>(storage: &S, id: u64) -> ReadonlyBucket<S, DelegatedVote> {
+    ReadonlyBucket::multilevel(&[DELEGATED_VOTES_KEY, &id.to_le_bytes()], storage)
+}
+
+/// stores the delegation of `delegator`, replacing any previous one
+pub fn set_delegation<S: Storage>(storage: &mut S, delegator: &str, delegation: &Delegation) -> StdResult<()> {
+    remove_delegation(storage, delegator)?;
+    delegations(storage).save(delegator.as_bytes(), delegation)?;
+    let mut list = delegators_read(storage).may_load(delegation.to.as_bytes())?.unwrap_or_default();
+    list.push(delegator.to_string());
+    delegators(storage).save(delegation.to.as_bytes(), &list)
+}
+
+/// removes the delegation of `delegator`, returning it if there was one
+pub fn remove_delegation<S: Storage>(storage: &mut S, delegator: &str) -> StdResult<Option<Delegation>> {
+    let delegation = delegations_read(storage).may_load(delegator.as_bytes())?;
+    if let Some(delegation) = &delegation {
+        delegations(storage).remove(delegator.as_bytes());
+        let mut list = delegators_read(storage).may_load(delegation.to.as_bytes())?.unwrap_or_default();
+        list.retain(|addr| addr != delegator);
+        delegators(storage).save(delegation.to.as_bytes(), &list)?;
+    }
+    Ok(delegation)
+}
+
+/// Voting power a member handed to another one. Delegations are not transitive:
+/// a delegate only ever casts its own weight and that of its direct delegators.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Delegation {
+    pub to: String,
+    pub until: Expiration,
+}
+
+impl Delegation {
+    pub fn is_active(&self, block: &BlockInfo) -> bool {
+        !self.until.is_expired(block)
+    }
+}
+
+/// Weight of a delegator that its delegate cast on a proposal
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DelegatedVote {
+    pub delegate: String,
+    pub weight: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Proposal {
     pub title: String,
     pub description: String,
+    /// member that created the proposal
+    pub proposer: String,
     pub start_height: u64,
+    pub start_time: u64,
     pub expires: crate::expiration::Expiration,
     pub msgs: Vec<CosmosMsg<Empty>>,
     pub status: Status,
@@ -97,99 +402,225 @@ pub struct Proposal {
     pub total_weight: u64,
     // summary of existing votes
     pub votes: Votes,
+    /// tallies of each house, empty without multi-house approval
+    #[serde(default)]
+    pub houses: Vec<HouseTally>,
+    /// status of each message when executed step by step, empty otherwise
+    #[serde(default)]
+    pub steps: Vec<StepStatus>,
+    /// block or time from which the proposal can be executed, if scheduled
+    #[serde(default)]
+    pub execute_at: Option<Expiration>,
+    /// proposals that must be executed before this one
+    #[serde(default)]
+    pub depends_on: Vec<u64>,
+}
+
+/// A proposal waiting in the due queue
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DueProposal {
+    pub proposal_id: u64,
+    pub execute_at: Expiration,
 }
 
 impl Proposal {
     /// current_status is non-mutable and returns what the status should be.
     /// (designed for queries)
-    pub fn current_status(&self, block: &BlockInfo) -> Status {
+    pub fn current_status(&self, block: &BlockInfo) -> StdResult<Status> {
         let mut status = self.status;
 
         // if open, check if voting is passed or timed out
-        if status == Status::Open && self.is_passed(block) {
+        if status == Status::Open && self.is_passed(block)? {
             status = Status::Passed;
         }
-        if status == Status::Open && (self.is_rejected(block) || self.expires.is_expired(block)) {
+        if status == Status::Open && (self.is_rejected(block)? || self.expires.is_expired(block)) {
             status = Status::Rejected;
         }
 
-        status
+        Ok(status)
     }
 
     /// update_status sets the status of the proposal to current_status.
     /// (designed for handler logic)
-    pub fn update_status(&mut self, block: &BlockInfo) {
-        self.status = self.current_status(block);
+    pub fn update_status(&mut self, block: &BlockInfo) -> StdResult<()> {
+        self.status = self.current_status(block)?;
+        Ok(())
     }
 
     /// Returns true if this proposal is sure to pass (even before expiration, if no future
-    /// sequence of possible votes could cause it to fail).
-    pub fn is_passed(&self, block: &BlockInfo) -> bool {
-        match self.threshold {
-            Threshold::AbsoluteCount {
-                weight: weight_needed,
-            } => self.votes.yes >= weight_needed,
-            Threshold::AbsolutePercentage {
-                percentage: percentage_needed,
-            } => {
-                self.votes.yes
-                    >= votes_needed(self.total_weight - self.votes.abstain, percentage_needed)
-            }
-            Threshold::ThresholdQuorum { threshold, quorum } => {
-                // we always require the quorum
-                if self.votes.total() < votes_needed(self.total_weight, quorum) {
-                    return false;
-                }
-                if self.expires.is_expired(block) {
-                    // If expired, we compare vote_count against the total number of votes (minus abstain).
-                    let opinions = self.votes.total() - self.votes.abstain;
-                    self.votes.yes >= votes_needed(opinions, threshold)
-                } else {
-                    // If not expired, we must assume all non-votes will be cast against
-                    let possible_opinions = self.total_weight - self.votes.abstain;
-                    self.votes.yes >= votes_needed(possible_opinions, threshold)
-                }
+    /// sequence of possible votes could cause it to fail). With houses, every one of
+    /// them must also pass.
+    pub fn is_passed(&self, block: &BlockInfo) -> StdResult<bool> {
+        let expired = self.expires.is_expired(block);
+        if !is_passed(&self.threshold, self.total_weight, &self.votes, expired)? {
+            return Ok(false);
+        }
+        for house in self.houses.iter() {
+            if !is_passed(&house.threshold, house.total_weight, &house.votes, expired)? {
+                return Ok(false);
             }
         }
+        Ok(true)
     }
 
     /// Returns true if this proposal is sure to be rejected (even before expiration, if
-    /// no future sequence of possible votes could cause it to pass).
-    pub fn is_rejected(&self, block: &BlockInfo) -> bool {
-        match self.threshold {
-            Threshold::AbsoluteCount {
-                weight: weight_needed,
-            } => {
-                let weight = self.total_weight - weight_needed;
-                self.votes.no > weight
+    /// no future sequence of possible votes could cause it to pass). With houses, it is
+    /// enough for one of them to reject it.
+    pub fn is_rejected(&self, block: &BlockInfo) -> StdResult<bool> {
+        let expired = self.expires.is_expired(block);
+        if is_rejected(&self.threshold, self.total_weight, &self.votes, expired)? {
+            return Ok(true);
+        }
+        for house in self.houses.iter() {
+            if is_rejected(&house.threshold, house.total_weight, &house.votes, expired)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// adds a vote to the tally, and to the tally of the voter's house if `group` is one
+    pub fn add_vote(&mut self, group: Option<&str>, vote: Vote, weight: u64) -> StdResult<()> {
+        self.votes.add_vote(vote, weight)?;
+        if let Some(house) = self.houses.iter_mut().find(|h| Some(h.group.as_str()) == group) {
+            house.votes.add_vote(vote, weight)?;
+        }
+        Ok(())
+    }
+
+    /// takes back weight previously added with `add_vote`
+    pub fn remove_vote(&mut self, group: Option<&str>, vote: Vote, weight: u64) -> StdResult<()> {
+        self.votes.remove_vote(vote, weight)?;
+        if let Some(house) = self.houses.iter_mut().find(|h| Some(h.group.as_str()) == group) {
+            house.votes.remove_vote(vote, weight)?;
+        }
+        Ok(())
+    }
+
+    /// Indexes of the messages executing `step` sends, or of all those not executed yet
+    /// without a step
+    pub fn steps_to_execute(&self, step: Option<u32>) -> StdResult<Vec<usize>> {
+        let step = match step {
+            Some(step) => step,
+            None => {
+                return Ok((0..self.msgs.len())
+                    .filter(|i| self.steps.get(*i) != Some(&StepStatus::Executed))
+                    .collect())
             }
-            Threshold::AbsolutePercentage {
-                percentage: percentage_needed,
-            } => {
-                self.votes.no
-                    > votes_needed(
-                        self.total_weight - self.votes.abstain,
-                        Decimal::one() - percentage_needed,
-                    )
+        };
+        if self.steps.is_empty() {
+            return Err(StdError::generic_err(ContractError::NotStepwise {}.to_string()));
+        }
+        match self.steps.get(step as usize) {
+            Some(StepStatus::Pending) => Ok(vec![step as usize]),
+            Some(StepStatus::Executed) => {
+                Err(StdError::generic_err(ContractError::StepAlreadyExecuted { step }.to_string()))
             }
-            Threshold::ThresholdQuorum {
-                threshold,
-                quorum: _,
-            } => {
-                if self.expires.is_expired(block) {
-                    // If expired, we compare vote_count against the total number of votes (minus abstain).
-                    let opinions = self.votes.total() - self.votes.abstain;
-                    self.votes.no > votes_needed(opinions, Decimal::one() - threshold)
-                } else {
-                    // If not expired, we must assume all non-votes will be cast for
-                    let possible_opinions = self.total_weight - self.votes.abstain;
-                    self.votes.no > votes_needed(possible_opinions, Decimal::one() - threshold)
-                }
+            None => Err(StdError::generic_err(ContractError::InvalidStep { step }.to_string())),
+        }
+    }
+
+    /// marks `steps` as executed, completing the proposal once none is left
+    pub fn mark_executed(&mut self, steps: &[usize]) {
+        for step in steps {
+            if let Some(status) = self.steps.get_mut(*step) {
+                *status = StepStatus::Executed;
             }
         }
+        self.status = if self.steps.iter().all(|status| *status == StepStatus::Executed) {
+            Status::Executed
+        } else {
+            Status::PartiallyExecuted
+        };
+    }
+
+    /// pass requirements, including those of each house
+    pub fn threshold_response(&self) -> ThresholdResponse {
+        let threshold = self.threshold.to_response(self.total_weight);
+        if self.houses.is_empty() {
+            return threshold;
+        }
+        ThresholdResponse::MultiHouse {
+            threshold: Box::new(threshold),
+            houses: self
+                .houses
+                .iter()
+                .map(|house| HouseThresholdResponse {
+                    group: house.group.clone(),
+                    threshold: house.threshold.to_response(house.total_weight),
+                })
+                .collect(),
+        }
     }
 }
 
+/// Returns true if `votes` out of `total_weight` are sure to reach `threshold`
+fn is_passed(threshold: &Threshold, total_weight: u64, votes: &Votes, expired: bool) -> StdResult<bool> {
+    let yes = u128::from(votes.yes);
+    Ok(match *threshold {
+        Threshold::AbsoluteCount {
+            weight: weight_needed,
+        } => votes.yes >= weight_needed,
+        Threshold::AbsolutePercentage {
+            percentage: percentage_needed,
+        } => yes >= votes_needed(without_abstain(total_weight.into(), votes)?, percentage_needed)?,
+        Threshold::ThresholdQuorum { threshold, quorum } => {
+            // we always require the quorum
+            if votes.total().u128() < votes_needed(total_weight.into(), quorum)? {
+                return Ok(false);
+            }
+            if expired {
+                // If expired, we compare vote_count against the total number of votes (minus abstain).
+                let opinions = without_abstain(votes.total().u128(), votes)?;
+                yes >= votes_needed(opinions, threshold)?
+            } else {
+                // If not expired, we must assume all non-votes will be cast against
+                let possible_opinions = without_abstain(total_weight.into(), votes)?;
+                yes >= votes_needed(possible_opinions, threshold)?
+            }
+        }
+    })
+}
+
+/// Returns true if `votes` out of `total_weight` can no longer reach `threshold`
+fn is_rejected(threshold: &Threshold, total_weight: u64, votes: &Votes, expired: bool) -> StdResult<bool> {
+    let no = u128::from(votes.no);
+    Ok(match *threshold {
+        Threshold::AbsoluteCount {
+            weight: weight_needed,
+        } => no + u128::from(weight_needed) > u128::from(total_weight),
+        Threshold::AbsolutePercentage {
+            percentage: percentage_needed,
+        } => {
+            no > votes_needed(
+                without_abstain(total_weight.into(), votes)?,
+                Decimal::one() - percentage_needed,
+            )?
+        }
+        Threshold::ThresholdQuorum {
+            threshold,
+            quorum: _,
+        } => {
+            if expired {
+                // If expired, we compare vote_count against the total number of votes (minus abstain).
+                let opinions = without_abstain(votes.total().u128(), votes)?;
+                no > votes_needed(opinions, Decimal::one() - threshold)?
+            } else {
+                // If not expired, we must assume all non-votes will be cast for
+                let possible_opinions = without_abstain(total_weight.into(), votes)?;
+                no > votes_needed(possible_opinions, Decimal::one() - threshold)?
+            }
+        }
+    })
+}
+
+// `weight` minus the abstained weight, which can't be more than the weight that could vote
+fn without_abstain(weight: u128, votes: &Votes) -> StdResult<u128> {
+    weight
+        .checked_sub(votes.abstain.into())
+        .ok_or_else(|| StdError::generic_err(ContractError::TallyUnderflow {}.to_string()))
+}
+
 // weight of votes for each option
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Votes {
@@ -200,9 +631,11 @@ pub struct Votes {
 }
 
 impl Votes {
-    /// sum of all votes
-    pub fn total(&self) -> u64 {
-        self.yes + self.no + self.abstain + self.veto
+    /// sum of all votes, which can be more than a u64 holds
+    pub fn total(&self) -> Uint128 {
+        Uint128(
+            u128::from(self.yes) + u128::from(self.no) + u128::from(self.abstain) + u128::from(self.veto),
+        )
     }
 
     /// create it with a yes vote for this much
@@ -215,22 +648,41 @@ impl Votes {
         }
     }
 
-    pub fn add_vote(&mut self, vote: Vote, weight: u64) {
+    /// fails without changing the tally if the option would overflow
+    pub fn add_vote(&mut self, vote: Vote, weight: u64) -> StdResult<()> {
+        let count = self.count_mut(vote);
+        *count = count
+            .checked_add(weight)
+            .ok_or_else(|| StdError::generic_err(ContractError::TallyOverflow {}.to_string()))?;
+        Ok(())
+    }
+
+    /// takes back weight previously added for this vote
+    pub fn remove_vote(&mut self, vote: Vote, weight: u64) -> StdResult<()> {
+        let count = self.count_mut(vote);
+        *count = count
+            .checked_sub(weight)
+            .ok_or_else(|| StdError::generic_err(ContractError::TallyUnderflow {}.to_string()))?;
+        Ok(())
+    }
+
+    fn count_mut(&mut self, vote: Vote) -> &mut u64 {
         match vote {
-            Vote::Yes => self.yes += weight,
-            Vote::Abstain => self.abstain += weight,
-            Vote::No => self.no += weight,
-            Vote::Veto => self.veto += weight,
+            Vote::Yes => &mut self.yes,
+            Vote::Abstain => &mut self.abstain,
+            Vote::No => &mut self.no,
+            Vote::Veto => &mut self.veto,
         }
     }
 }
 
-// this is a helper function so Decimal works with u64 rather than Uint128
+// this is a helper function so Decimal works with vote weights rather than Uint128
 // also, we must *round up* here, as we need 8, not 7 votes to reach 50% of 15 total
-fn votes_needed(weight: u64, percentage: Decimal) -> u64 {
-    let applied = percentage * Uint128::from(PRECISION_FACTOR * weight as u128);
-    // Divide by PRECISION_FACTOR, rounding up to the nearest integer
-    ((applied.u128() + PRECISION_FACTOR - 1) / PRECISION_FACTOR) as u64
+pub(crate) fn votes_needed(weight: u128, percentage: Decimal) -> StdResult<u128> {
+    Uint128(weight)
+        .checked_mul_ceil(percentage)
+        .map(|needed| needed.u128())
+        .ok_or_else(|| StdError::generic_err(ContractError::TallyOverflow {}.to_string()))
 }
 
 // we cast a ballot with our chosen vote and a given weight
@@ -252,30 +704,68 @@ mod test {
     #[test]
     fn count_votes() {
         let mut votes = Votes::yes(5);
-        votes.add_vote(Vote::No, 10);
-        votes.add_vote(Vote::Veto, 20);
-        votes.add_vote(Vote::Yes, 30);
-        votes.add_vote(Vote::Abstain, 40);
+        votes.add_vote(Vote::No, 10).unwrap();
+        votes.add_vote(Vote::Veto, 20).unwrap();
+        votes.add_vote(Vote::Yes, 30).unwrap();
+        votes.add_vote(Vote::Abstain, 40).unwrap();
 
-        assert_eq!(votes.total(), 105);
+        assert_eq!(votes.total(), Uint128(105));
         assert_eq!(votes.yes, 35);
         assert_eq!(votes.no, 10);
         assert_eq!(votes.veto, 20);
         assert_eq!(votes.abstain, 40);
+
+        votes.remove_vote(Vote::Yes, 30).unwrap();
+        assert_eq!(votes.total(), Uint128(75));
+        assert_eq!(votes.yes, 5);
     }
 
     #[test]
     // we ensure this rounds up (as it calculates needed votes)
     fn votes_needed_rounds_properly() {
         // round up right below 1
-        assert_eq!(1, votes_needed(3, Decimal::permille(333)));
+        assert_eq!(1, votes_needed(3, Decimal::permille(333)).unwrap());
         // round up right over 1
-        assert_eq!(2, votes_needed(3, Decimal::permille(334)));
-        assert_eq!(11, votes_needed(30, Decimal::permille(334)));
+        assert_eq!(2, votes_needed(3, Decimal::permille(334)).unwrap());
+        assert_eq!(11, votes_needed(30, Decimal::permille(334)).unwrap());
 
         // exact matches don't round
-        assert_eq!(17, votes_needed(34, Decimal::percent(50)));
-        assert_eq!(12, votes_needed(48, Decimal::percent(25)));
+        assert_eq!(17, votes_needed(34, Decimal::percent(50)).unwrap());
+        assert_eq!(12, votes_needed(48, Decimal::percent(25)).unwrap());
+    }
+
+    #[test]
+    fn count_votes_near_u64_max() {
+        let mut votes = Votes::yes(u64::MAX);
+        votes.add_vote(Vote::No, u64::MAX).unwrap();
+        votes.add_vote(Vote::Abstain, u64::MAX).unwrap();
+        votes.add_vote(Vote::Veto, u64::MAX).unwrap();
+        assert_eq!(votes.total(), Uint128(4 * u128::from(u64::MAX)));
+
+        // a full option fails without changing the tally
+        let err = votes.add_vote(Vote::Yes, 1).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::TallyOverflow {}.to_string()));
+        assert_eq!(votes.yes, u64::MAX);
+
+        votes.remove_vote(Vote::No, u64::MAX).unwrap();
+        let err = votes.remove_vote(Vote::No, 1).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::TallyUnderflow {}.to_string()));
+        assert_eq!(votes.no, 0);
+    }
+
+    #[test]
+    fn votes_needed_near_u64_max() {
+        let max = u128::from(u64::MAX);
+        assert_eq!(votes_needed(max, Decimal::percent(50)).unwrap(), max / 2 + 1);
+        assert_eq!(votes_needed(max, Decimal::one()).unwrap(), max);
+        assert_eq!(votes_needed(max - 1, Decimal::percent(50)).unwrap(), (max - 1) / 2);
+        // the most a tally of opinions can hold, 3 full options
+        assert_eq!(votes_needed(3 * max, Decimal::permille(334)).unwrap(), (3 * max * 334).div_ceil(1000));
+        assert_eq!(votes_needed(0, Decimal::one()).unwrap(), 0);
+
+        // only percentages over 100% can overflow
+        let err = votes_needed(u128::MAX, Decimal::percent(200)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::TallyOverflow {}.to_string()));
     }
 
 
@@ -295,13 +785,19 @@ mod test {
         let prop = Proposal {
             title: "Demo".to_string(),
             description: "Info".to_string(),
+            proposer: "proposer".to_string(),
             start_height: 100,
+            start_time: 1_571_797_000,
             expires,
             msgs: vec![],
             status: Status::Open,
             threshold,
             total_weight,
             votes,
+            houses: vec![],
+            steps: vec![],
+            execute_at: None,
+            depends_on: vec![],
         };
 
         (prop, block)
@@ -314,7 +810,7 @@ mod test {
         is_expired: bool,
     ) -> bool {
         let (prop, block) = setup_prop(threshold, votes, total_weight, is_expired);
-        prop.is_passed(&block)
+        prop.is_passed(&block).unwrap()
     }
 
     fn check_is_rejected(
@@ -324,19 +820,63 @@ mod test {
         is_expired: bool,
     ) -> bool {
         let (prop, block) = setup_prop(threshold, votes, total_weight, is_expired);
-        prop.is_rejected(&block)
+        prop.is_rejected(&block).unwrap()
+    }
+
+    #[test]
+    fn tally_near_u64_max() {
+        let fixed = Threshold::AbsoluteCount { weight: u64::MAX };
+        let mut votes = Votes::yes(u64::MAX - 1);
+        assert!(!check_is_passed(fixed.clone(), votes.clone(), u64::MAX, false));
+        assert!(!check_is_rejected(fixed.clone(), votes.clone(), u64::MAX, false));
+        // a single no vote makes the threshold unreachable
+        votes.add_vote(Vote::No, 1).unwrap();
+        assert!(check_is_rejected(fixed.clone(), votes.clone(), u64::MAX, false));
+        let votes = Votes::yes(u64::MAX);
+        assert!(check_is_passed(fixed.clone(), votes.clone(), u64::MAX, false));
+        assert!(!check_is_rejected(fixed, votes, u64::MAX, false));
+
+        // percentages of such weights are exact
+        let percent = Threshold::AbsolutePercentage { percentage: Decimal::percent(50) };
+        let votes = Votes::yes(u64::MAX / 2);
+        assert!(!check_is_passed(percent.clone(), votes, u64::MAX, false));
+        let mut votes = Votes::yes(u64::MAX / 2 + 1);
+        assert!(check_is_passed(percent.clone(), votes.clone(), u64::MAX, false));
+        votes.add_vote(Vote::Abstain, u64::MAX - votes.yes).unwrap();
+        assert!(check_is_passed(percent, votes, u64::MAX, true));
+        let quorum = Threshold::ThresholdQuorum {
+            threshold: Decimal::percent(50),
+            quorum: Decimal::percent(40),
+        };
+        let mut votes = Votes::yes(u64::MAX / 4);
+        votes.add_vote(Vote::No, u64::MAX / 4 + 1).unwrap();
+        votes.add_vote(Vote::Abstain, u64::MAX / 4).unwrap();
+        assert!(!check_is_passed(quorum.clone(), votes.clone(), u64::MAX, true));
+        votes.add_vote(Vote::Yes, 1).unwrap();
+        assert!(check_is_passed(quorum, votes, u64::MAX, true));
+
+        // more abstained weight than there is to vote with is an error, not a wrap around
+        let quorum = Threshold::ThresholdQuorum {
+            threshold: Decimal::percent(50),
+            quorum: Decimal::percent(40),
+        };
+        let mut votes = Votes::yes(0);
+        votes.add_vote(Vote::Abstain, 31).unwrap();
+        let (prop, block) = setup_prop(quorum, votes, 30, false);
+        let err = prop.current_status(&block).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::TallyUnderflow {}.to_string()));
     }
 
     #[test]
     fn proposal_passed_absolute_count() {
         let fixed = Threshold::AbsoluteCount { weight: 10 };
         let mut votes = Votes::yes(7);
-        votes.add_vote(Vote::Veto, 4);
+        votes.add_vote(Vote::Veto, 4).unwrap();
         // same expired or not, total_weight or whatever
         assert!(!check_is_passed(fixed.clone(), votes.clone(), 30, false));
         assert!(!check_is_passed(fixed.clone(), votes.clone(), 30, true));
         // a few more yes votes and we are good
-        votes.add_vote(Vote::Yes, 3);
+        votes.add_vote(Vote::Yes, 3).unwrap();
         assert!(check_is_passed(fixed.clone(), votes.clone(), 30, false));
         assert!(check_is_passed(fixed, votes, 30, true));
     }
@@ -345,13 +885,13 @@ mod test {
     fn proposal_rejected_absolute_count() {
         let fixed = Threshold::AbsoluteCount { weight: 10 };
         let mut votes = Votes::yes(0);
-        votes.add_vote(Vote::Veto, 4);
-        votes.add_vote(Vote::No, 7);
+        votes.add_vote(Vote::Veto, 4).unwrap();
+        votes.add_vote(Vote::No, 7).unwrap();
         // In order to reject the proposal we need no votes > 30 - 10, currently it is not rejected
         assert!(!check_is_rejected(fixed.clone(), votes.clone(), 30, false));
         assert!(!check_is_rejected(fixed.clone(), votes.clone(), 30, true));
         // 7 + 14 = 21 > 20, we can now reject
-        votes.add_vote(Vote::No, 14);
+        votes.add_vote(Vote::No, 14).unwrap();
         assert!(check_is_rejected(fixed.clone(), votes.clone(), 30, false));
         assert!(check_is_rejected(fixed, votes, 30, true));
     }
@@ -362,8 +902,8 @@ mod test {
             percentage: Decimal::percent(50),
         };
         let mut votes = Votes::yes(7);
-        votes.add_vote(Vote::No, 4);
-        votes.add_vote(Vote::Abstain, 2);
+        votes.add_vote(Vote::No, 4).unwrap();
+        votes.add_vote(Vote::Abstain, 2).unwrap();
         // same expired or not, if yes >= ceiling(0.5 * (total - abstained))
         // 7 of (15-2) passes
         assert!(check_is_passed(percent.clone(), votes.clone(), 15, false));
@@ -384,8 +924,8 @@ mod test {
 
         // 4 YES, 7 NO, 2 ABSTAIN
         let mut votes = Votes::yes(4);
-        votes.add_vote(Vote::No, 7);
-        votes.add_vote(Vote::Abstain, 2);
+        votes.add_vote(Vote::No, 7).unwrap();
+        votes.add_vote(Vote::Abstain, 2).unwrap();
 
         // 15 total voting power
         // we need no votes > 0.4 * 15, no votes > 6
@@ -1,9 +1,11 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{StdError};
+use cosmwasm_std::{StdError, StdResult};
 use thiserror::Error;
+use crate::house::HouseThresholdResponse;
 use crate::math::Decimal;
+use crate::state::votes_needed;
 
 
 /// This defines the different ways tallies can happen.
@@ -60,6 +62,18 @@ impl Threshold {
         }
     }
 
+    /// Returns the weight of yes votes needed to pass if every member votes,
+    /// used to compare how strict different thresholds are
+    pub fn required_weight(&self, total_weight: u64) -> StdResult<u128> {
+        match self {
+            Threshold::AbsoluteCount { weight } => Ok((*weight).into()),
+            Threshold::AbsolutePercentage { percentage } => votes_needed(total_weight.into(), *percentage),
+            Threshold::ThresholdQuorum { threshold, quorum: _ } => {
+                votes_needed(total_weight.into(), *threshold)
+            }
+        }
+    }
+
     /// Creates a response from the saved data, just missing the total_weight info
     pub fn to_response(&self, total_weight: u64) -> ThresholdResponse {
         match self.clone() {
@@ -169,6 +183,13 @@ pub enum ThresholdResponse {
         quorum: Decimal,
         total_weight: u64,
     },
+
+    /// Multi-house approval: `threshold` must be reached by the whole multisig, and each
+    /// house must reach its own threshold among its members.
+    MultiHouse {
+        threshold: Box<ThresholdResponse>,
+        houses: Vec<HouseThresholdResponse>,
+    },
 }
 
 #[derive(Error, Debug, PartialEq)]
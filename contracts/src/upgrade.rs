@@ -0,0 +1,223 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Api, Binary, CanonicalAddr, CosmosMsg, Empty, HumanAddr, StdResult, Storage};
+use cosmwasm_storage::{bucket, bucket_read};
+
+use crate::expiration::Expiration;
+use crate::query::Status;
+use crate::state::{open_proposals_read, proposal_count_read, proposals_read, schema_version_read, Proposal, Votes,
+                   PROPOSALS_KEY};
+use crate::threshold::Threshold;
+
+/// Version of the storage layout written by this code.
+///
+/// 1. v0.1, no version stored
+/// 2. proposals record their proposer, start time, house tallies and deposit
+/// 3. voters and ballots are keyed by canonical address
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Version of the layout the contract was deployed with. Entries written before an
+/// upgrade may be in any layout from this version on.
+pub fn stored_schema_version<S: Storage>(storage: &S) -> StdResult<u32> {
+    Ok(schema_version_read(storage).may_load()?.unwrap_or(1))
+}
+
+/// Key of `addr` in the voters and ballots buckets. `addr` is always validated, but
+/// contracts deployed before schema version 3 keep their human address keys.
+pub fn member_key<S: Storage, A: Api>(storage: &S, api: &A, addr: &HumanAddr) -> StdResult<CanonicalAddr> {
+    let canonical = api.canonical_address(addr)?;
+    if stored_schema_version(storage)? < 3 {
+        return Ok(CanonicalAddr(Binary::from(addr.as_str().as_bytes())));
+    }
+    Ok(canonical)
+}
+
+/// Ids in the open proposals index. v0.1 contracts have no index until a proposal opens or
+/// closes after the upgrade, so their open proposals are found by scanning them all.
+pub fn open_proposal_ids<S: Storage>(storage: &S) -> StdResult<Vec<u64>> {
+    if let Some(open) = open_proposals_read(storage).may_load()? {
+        return Ok(open);
+    }
+    if stored_schema_version(storage)? > 1 {
+        return Ok(vec![]);
+    }
+    let count = proposal_count_read(storage).may_load()?.unwrap_or(0);
+    let mut open = vec![];
+    for id in 1..=count {
+        if let Some(prop) = proposals_read(storage).may_load(&id.to_le_bytes())? {
+            if prop.status == Status::Open {
+                open.push(id);
+            }
+        }
+    }
+    Ok(open)
+}
+
+/// `Proposal` as stored by schema version 1
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ProposalV1 {
+    pub title: String,
+    pub description: String,
+    pub start_height: u64,
+    pub expires: Expiration,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub status: Status,
+    pub threshold: Threshold,
+    pub total_weight: u64,
+    pub votes: Votes,
+}
+
+impl From<ProposalV1> for Proposal {
+    /// the proposer and start time were not recorded, and are left empty
+    fn from(prop: ProposalV1) -> Self {
+        Proposal {
+            title: prop.title,
+            description: prop.description,
+            proposer: String::new(),
+            start_height: prop.start_height,
+            start_time: 0,
+            expires: prop.expires,
+            msgs: prop.msgs,
+            status: prop.status,
+            threshold: prop.threshold,
+            total_weight: prop.total_weight,
+            votes: prop.votes,
+            houses: vec![],
+            steps: vec![],
+            execute_at: None,
+            depends_on: vec![],
+        }
+    }
+}
+
+/// may_load for proposals of any layout, upgraded to the current one
+fn may_load_proposal<S: Storage>(storage: &S, key: &[u8]) -> StdResult<Option<Proposal>> {
+    let err = match bucket_read::<S, Proposal>(PROPOSALS_KEY, storage).may_load(key) {
+        Ok(prop) => return Ok(prop),
+        Err(err) => err,
+    };
+    if stored_schema_version(storage)? >= 2 {
+        return Err(err);
+    }
+    bucket_read::<S, ProposalV1>(PROPOSALS_KEY, storage)
+        .may_load(key)
+        .map(|prop| prop.map(Proposal::from))
+        .map_err(|_| err)
+}
+
+fn load_proposal<S: Storage>(storage: &S, key: &[u8]) -> StdResult<Proposal> {
+    match may_load_proposal(storage, key)? {
+        Some(prop) => Ok(prop),
+        // same error as a plain bucket
+        None => bucket_read::<S, Proposal>(PROPOSALS_KEY, storage).load(key),
+    }
+}
+
+/// Proposals bucket reading older layouts. Proposals are written in the current layout,
+/// so an old proposal is upgraded the next time it is saved.
+pub struct ProposalBucket<'a, S: Storage> {
+    storage: &'a mut S,
+}
+
+impl<'a, S: Storage> ProposalBucket<'a, S> {
+    pub fn new(storage: &'a mut S) -> Self {
+        ProposalBucket { storage }
+    }
+
+    pub fn save(&mut self, key: &[u8], data: &Proposal) -> StdResult<()> {
+        bucket(PROPOSALS_KEY, self.storage).save(key, data)
+    }
+
+    pub fn remove(&mut self, key: &[u8]) {
+        bucket::<S, Proposal>(PROPOSALS_KEY, self.storage).remove(key)
+    }
+
+    pub fn load(&self, key: &[u8]) -> StdResult<Proposal> {
+        load_proposal(self.storage, key)
+    }
+
+    pub fn may_load(&self, key: &[u8]) -> StdResult<Option<Proposal>> {
+        may_load_proposal(self.storage, key)
+    }
+}
+
+pub struct ReadonlyProposalBucket<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> ReadonlyProposalBucket<'a, S> {
+    pub fn new(storage: &'a S) -> Self {
+        ReadonlyProposalBucket { storage }
+    }
+
+    pub fn load(&self, key: &[u8]) -> StdResult<Proposal> {
+        load_proposal(self.storage, key)
+    }
+
+    pub fn may_load(&self, key: &[u8]) -> StdResult<Option<Proposal>> {
+        may_load_proposal(self.storage, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{BankMsg, HumanAddr, coin};
+    use cosmwasm_storage::to_length_prefixed;
+
+    use crate::state::{proposals, proposals_read, schema_version};
+
+    use super::*;
+
+    const PROPOSAL_V1: &str = include_str!("../testdata/v0.1/proposal.json");
+
+    fn store_raw(storage: &mut MockStorage, id: u64, value: &str) {
+        let key = [to_length_prefixed(PROPOSALS_KEY), id.to_le_bytes().to_vec()].concat();
+        storage.set(&key, value.trim_end().as_bytes());
+    }
+
+    #[test]
+    fn v1_proposals_are_upgraded() {
+        let mut storage = MockStorage::new();
+        store_raw(&mut storage, 1, PROPOSAL_V1);
+
+        let prop = proposals_read(&storage).load(&1u64.to_le_bytes()).unwrap();
+        assert_eq!(prop.title, "Pay somebody");
+        assert_eq!(prop.proposer, "");
+        assert_eq!(prop.start_height, 12345);
+        assert_eq!(prop.start_time, 0);
+        assert_eq!(prop.expires, Expiration::AtTime(1573797419));
+        assert_eq!(
+            prop.msgs,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: HumanAddr::from("owner"),
+                to_address: HumanAddr::from("somebody"),
+                amount: vec![coin(1, "BTC")],
+            })]
+        );
+        assert_eq!(prop.status, Status::Open);
+        assert_eq!(prop.threshold, Threshold::AbsoluteCount { weight: 4 });
+        assert_eq!(prop.total_weight, 6);
+        assert_eq!(prop.votes.yes, 1);
+        assert_eq!(prop.houses, vec![]);
+        assert_eq!(proposals_read(&storage).may_load(&2u64.to_le_bytes()).unwrap(), None);
+
+        // saving writes the current layout
+        proposals(&mut storage).save(&1u64.to_le_bytes(), &prop).unwrap();
+        let current = bucket_read::<_, Proposal>(PROPOSALS_KEY, &storage).load(&1u64.to_le_bytes());
+        assert_eq!(current.unwrap(), prop);
+    }
+
+    #[test]
+    fn current_deployments_only_read_current_layout() {
+        let mut storage = MockStorage::new();
+        schema_version(&mut storage).save(&SCHEMA_VERSION).unwrap();
+        store_raw(&mut storage, 1, PROPOSAL_V1);
+
+        let err = proposals_read(&storage).load(&1u64.to_le_bytes()).unwrap_err();
+        let expected = bucket_read::<_, Proposal>(PROPOSALS_KEY, &storage).load(&1u64.to_le_bytes());
+        assert_eq!(err, expected.unwrap_err());
+        assert!(proposals_read(&storage).load(&2u64.to_le_bytes()).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use cosmwasm_std::{to_vec, Api, BankMsg, CosmosMsg, HumanAddr, StdError, StdResult, WasmMsg};
+
+use crate::error::ContractError;
+
+/// Longest title accepted, in bytes
+pub const MAX_TITLE_LENGTH: usize = 256;
+/// Longest description accepted, in bytes
+pub const MAX_DESCRIPTION_LENGTH: usize = 4096;
+/// Most messages a proposal can hold
+pub const MAX_MSGS: usize = 16;
+/// Largest size of a proposal's messages once serialized, in bytes
+pub const MAX_MSGS_SIZE: usize = 16 * 1024;
+/// Most votes a `VoteBatch` can cast
+pub const MAX_BATCH_VOTES: usize = 30;
+
+/// Checks a proposal before it is stored, so that a message that can only fail is refused
+/// now rather than after everyone voted on it. Only signalling proposals may have no
+/// messages.
+pub fn validate_proposal<A: Api>(
+    api: &A,
+    contract: &HumanAddr,
+    title: &str,
+    description: &str,
+    msgs: &[CosmosMsg],
+    signal: bool,
+) -> StdResult<()> {
+    if title.len() > MAX_TITLE_LENGTH {
+        return Err(error(ContractError::TitleTooLong { max: MAX_TITLE_LENGTH }));
+    }
+    if description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(error(ContractError::DescriptionTooLong { max: MAX_DESCRIPTION_LENGTH }));
+    }
+    if msgs.is_empty() && !signal {
+        return Err(error(ContractError::EmptyProposal {}));
+    }
+    if msgs.len() > MAX_MSGS {
+        return Err(error(ContractError::TooManyMessages { max: MAX_MSGS }));
+    }
+    if to_vec(msgs)?.len() > MAX_MSGS_SIZE {
+        return Err(error(ContractError::MessagesTooLarge { max: MAX_MSGS_SIZE }));
+    }
+
+    for msg in msgs {
+        match msg {
+            CosmosMsg::Bank(BankMsg::Send { from_address, to_address, .. }) => {
+                // the multisig can only send its own funds
+                if from_address != contract {
+                    return Err(error(ContractError::InvalidSender {}));
+                }
+                check_recipient(api, to_address)?;
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => check_recipient(api, contract_addr)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_recipient<A: Api>(api: &A, addr: &HumanAddr) -> StdResult<()> {
+    api.canonical_address(addr)
+        .map(|_| ())
+        .map_err(|_| error(ContractError::InvalidRecipient { address: addr.to_string() }))
+}
+
+fn error(err: ContractError) -> StdError {
+    StdError::generic_err(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::{coin, Binary};
+
+    use super::*;
+
+    const CONTRACT: &str = "multisig";
+
+    fn send(from: &str, to: &str) -> CosmosMsg {
+        CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from(from),
+            to_address: HumanAddr::from(to),
+            amount: vec![coin(1, "uscrt")],
+        })
+    }
+
+    fn validate(title: &str, description: &str, msgs: &[CosmosMsg], signal: bool) -> StdResult<()> {
+        validate_proposal(&MockApi::new(20), &HumanAddr::from(CONTRACT), title, description, msgs, signal)
+    }
+
+    #[test]
+    fn validate_proposal_works() {
+        validate("Pay", "", &[send(CONTRACT, "somebody")], false).unwrap();
+        validate("Poll", "Do we agree?", &[], true).unwrap();
+
+        let err = validate("Pay", "", &[], false).unwrap_err();
+        assert_eq!(err, error(ContractError::EmptyProposal {}));
+        let err = validate("Pay", "", &[send("somebody", CONTRACT)], false).unwrap_err();
+        assert_eq!(err, error(ContractError::InvalidSender {}));
+        let err = validate("Pay", "", &[send(CONTRACT, "x")], false).unwrap_err();
+        assert_eq!(err, error(ContractError::InvalidRecipient { address: "x".to_string() }));
+        let execute = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("a contract address that is too long"),
+            callback_code_hash: "hash".to_string(),
+            msg: Binary::from(b"{}".to_vec()),
+            send: vec![],
+        });
+        let err = validate("Call", "", &[execute], false).unwrap_err();
+        assert!(err.to_string().contains("a contract address that is too long"));
+    }
+
+    #[test]
+    fn validate_proposal_sizes() {
+        let msg = send(CONTRACT, "somebody");
+        let msgs = vec![msg.clone()];
+        let long_title = "t".repeat(MAX_TITLE_LENGTH + 1);
+        let err = validate(&long_title, "", &msgs, false).unwrap_err();
+        assert_eq!(err, error(ContractError::TitleTooLong { max: MAX_TITLE_LENGTH }));
+        let long_description = "d".repeat(MAX_DESCRIPTION_LENGTH + 1);
+        let err = validate("Pay", &long_description, &msgs, false).unwrap_err();
+        assert_eq!(err, error(ContractError::DescriptionTooLong { max: MAX_DESCRIPTION_LENGTH }));
+
+        validate("Pay", "", &vec![msg.clone(); MAX_MSGS], false).unwrap();
+        let err = validate("Pay", "", &vec![msg; MAX_MSGS + 1], false).unwrap_err();
+        assert_eq!(err, error(ContractError::TooManyMessages { max: MAX_MSGS }));
+
+        let large = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("token"),
+            callback_code_hash: "hash".to_string(),
+            msg: Binary(vec![0; MAX_MSGS_SIZE]),
+            send: vec![],
+        });
+        let err = validate("Call", "", &[large], false).unwrap_err();
+        assert_eq!(err, error(ContractError::MessagesTooLarge { max: MAX_MSGS_SIZE }));
+    }
+}
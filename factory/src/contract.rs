@@ -0,0 +1,307 @@
+use cosmwasm_std::{
+    log, to_binary, Api, Binary, CosmosMsg, Empty, Env, Extern, HandleResponse, HumanAddr,
+    InitResponse, Querier, StdError, StdResult, Storage, WasmMsg };
+use snip3_fixed_multisig::expiration::Duration;
+use snip3_fixed_multisig::msg::{ InitCallback, InitMsg as MultisigInitMsg, Voter };
+use snip3_fixed_multisig::threshold::Threshold;
+
+use crate::error::ContractError;
+use crate::msg::{ ConfigResponse, HandleMsg, InitMsg, MultisigListResponse, QueryMsg };
+use crate::state::{ config, config_read, multisig_count, multisig_count_read, pending, pending_read,
+                    registry, registry_read, Config, MultisigInfo, PendingMultisig };
+
+pub fn init<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    _env: Env,
+    msg: InitMsg,
+) -> Result<InitResponse, StdError> {
+    let cfg = Config {
+        multisig_code_id: msg.multisig_code_id,
+        multisig_code_hash: msg.multisig_code_hash,
+    };
+    config(&mut deps.storage).save(&cfg)?;
+    multisig_count(&mut deps.storage).save(&0)?;
+
+    Ok(InitResponse::default())
+}
+
+pub fn handle<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    msg: HandleMsg,
+) -> Result<HandleResponse<Empty>, StdError> {
+    match msg {
+        HandleMsg::CreateMultisig {
+            voters,
+            threshold,
+            max_voting_period,
+            label,
+        } => execute_create_multisig(deps, env, voters, threshold, max_voting_period, label),
+        HandleMsg::RegisterMultisig { id } => execute_register_multisig(deps, env, id),
+    }
+}
+
+pub fn execute_create_multisig<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    voters: Vec<Voter>,
+    threshold: Threshold,
+    max_voting_period: Duration,
+    label: String,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // the rest is validated by the multisig itself
+    if label.is_empty() {
+        return Err(StdError::generic_err(ContractError::EmptyLabel {}.to_string()));
+    }
+
+    let cfg = config_read(&deps.storage).load()?;
+    let id = multisig_count(&mut deps.storage).update(|mut id| {
+        id += 1;
+        Ok(id)
+    })?;
+
+    // remember who to register the multisig for once it calls back with its address
+    let entry = PendingMultisig {
+        label: label.clone(),
+        code_hash: cfg.multisig_code_hash.clone(),
+        members: voters.iter().map(|v| v.addr.clone()).collect(),
+    };
+    pending(&mut deps.storage).save(&id.to_le_bytes(), &entry)?;
+
+    let init_msg = MultisigInitMsg {
+        voters,
+        threshold,
+        max_voting_period,
+        proposers: None,
+        proposal_deposit: None,
+        threshold_rules: None,
+        guardians: None,
+        houses: None,
+        import_state: None,
+        callback: Some(InitCallback {
+            contract_addr: env.contract.address,
+            code_hash: env.contract_code_hash,
+            id,
+        }),
+    };
+    let instantiate = CosmosMsg::Wasm(WasmMsg::Instantiate {
+        code_id: cfg.multisig_code_id,
+        callback_code_hash: cfg.multisig_code_hash,
+        msg: to_binary(&init_msg)?,
+        send: env.message.sent_funds,
+        label: label.clone(),
+    });
+
+    Ok(HandleResponse {
+        messages: vec![instantiate],
+        log: vec![
+            log("action", "create_multisig"),
+            log("sender", env.message.sender),
+            log("label", label)],
+        data: None
+    })
+}
+
+pub fn execute_register_multisig<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    id: u64,
+) -> Result<HandleResponse<Empty>, StdError> {
+    // the sender is the multisig instantiated for this id
+    let entry = pending_read(&deps.storage)
+        .may_load(&id.to_le_bytes())?
+        .ok_or_else(|| StdError::generic_err(ContractError::UnknownPendingMultisig {}.to_string()))?;
+    pending(&mut deps.storage).remove(&id.to_le_bytes());
+
+    let info = MultisigInfo {
+        address: env.message.sender.clone(),
+        code_hash: entry.code_hash,
+        label: entry.label,
+    };
+    for member in entry.members {
+        let mut list = registry_read(&deps.storage).may_load(member.as_bytes())?.unwrap_or_default();
+        list.push(info.clone());
+        registry(&mut deps.storage).save(member.as_bytes(), &list)?;
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "register_multisig"),
+            log("multisig", env.message.sender)],
+        data: None
+    })
+}
+
+pub fn query<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::ListMultisigs {
+            member,
+            start_after,
+            limit,
+        } => to_binary(&list_multisigs(deps, member, start_after, limit)?),
+    }
+}
+
+fn query_config<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<ConfigResponse> {
+    let cfg = config_read(&deps.storage).load()?;
+    let multisig_count = multisig_count_read(&deps.storage).load()?;
+    Ok(ConfigResponse {
+        multisig_code_id: cfg.multisig_code_id,
+        multisig_code_hash: cfg.multisig_code_hash,
+        multisig_count,
+    })
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+fn list_multisigs<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    member: String,
+    start_after: Option<HumanAddr>,
+    limit: Option<u32>,
+) -> StdResult<MultisigListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let list = registry_read(&deps.storage).may_load(member.as_bytes())?.unwrap_or_default();
+
+    // start right after `start_after`, or from the beginning if it is not in the list
+    let start = start_after
+        .and_then(|addr| list.iter().position(|m| m.address == addr))
+        .map_or(0, |pos| pos + 1);
+    let multisigs = list.into_iter().skip(start).take(limit).collect();
+
+    Ok(MultisigListResponse { multisigs })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coin, from_binary};
+    use snip3_fixed_multisig::msg::FactoryCallbackMsg;
+
+    use super::*;
+
+    const OWNER: &str = "admin0001";
+    const VOTER1: &str = "voter0001";
+    const VOTER2: &str = "voter0002";
+
+    fn voter(addr: &str, weight: u64) -> Voter {
+        Voter {
+            addr: addr.to_string(),
+            weight,
+            group: None,
+        }
+    }
+
+    fn create_msg(voters: Vec<Voter>, label: &str) -> HandleMsg {
+        HandleMsg::CreateMultisig {
+            voters,
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            max_voting_period: Duration::Height(100),
+            label: label.to_string(),
+        }
+    }
+
+    fn list<S: Storage, A: Api, Q: Querier>(
+        deps: &Extern<S, A, Q>,
+        member: &str,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> Vec<MultisigInfo> {
+        let msg = QueryMsg::ListMultisigs {
+            member: member.to_string(),
+            start_after: start_after.map(HumanAddr::from),
+            limit,
+        };
+        let res: MultisigListResponse = from_binary(&query(deps, msg).unwrap()).unwrap();
+        res.multisigs
+    }
+
+    #[test]
+    fn create_and_register_multisigs() {
+        let mut deps = mock_dependencies(20, &[]);
+        let init_msg = InitMsg {
+            multisig_code_id: 7,
+            multisig_code_hash: "multisig_hash".to_string(),
+        };
+        init(&mut deps, mock_env(OWNER, &[]), init_msg).unwrap();
+
+        let err = handle(&mut deps, mock_env(OWNER, &[]), create_msg(vec![voter(VOTER1, 1)], "")).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::EmptyLabel {}.to_string()));
+
+        // the multisig is instantiated with a callback registering it
+        let voters = vec![voter(VOTER1, 1), voter(VOTER2, 1)];
+        let funds = vec![coin(100, "uscrt")];
+        let res = handle(&mut deps, mock_env(OWNER, &funds), create_msg(voters.clone(), "client a")).unwrap();
+        let env = mock_env(OWNER, &[]);
+        let expected_init = MultisigInitMsg {
+            voters,
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            max_voting_period: Duration::Height(100),
+            proposers: None,
+            proposal_deposit: None,
+            threshold_rules: None,
+            guardians: None,
+            houses: None,
+            import_state: None,
+            callback: Some(InitCallback {
+                contract_addr: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                code_hash: env.contract_code_hash,
+                id: 1,
+            }),
+        };
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Instantiate {
+                code_id: 7,
+                callback_code_hash: "multisig_hash".to_string(),
+                msg: to_binary(&expected_init).unwrap(),
+                send: funds,
+                label: "client a".to_string(),
+            })]
+        );
+
+        // which the multisig sends back as our registration message
+        assert_eq!(
+            to_binary(&FactoryCallbackMsg::RegisterMultisig { id: 1 }).unwrap(),
+            to_binary(&HandleMsg::RegisterMultisig { id: 1 }).unwrap()
+        );
+
+        // only a pending multisig can register, and only once
+        let register = HandleMsg::RegisterMultisig { id: 2 };
+        let err = handle(&mut deps, mock_env("multisig_b", &[]), register).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::UnknownPendingMultisig {}.to_string()));
+        let register = HandleMsg::RegisterMultisig { id: 1 };
+        handle(&mut deps, mock_env("multisig_a", &[]), register.clone()).unwrap();
+        let err = handle(&mut deps, mock_env("multisig_a", &[]), register).unwrap_err();
+        assert_eq!(err, StdError::generic_err(ContractError::UnknownPendingMultisig {}.to_string()));
+
+        let multisig_a = MultisigInfo {
+            address: HumanAddr::from("multisig_a"),
+            code_hash: "multisig_hash".to_string(),
+            label: "client a".to_string(),
+        };
+        assert_eq!(list(&deps, VOTER1, None, None), vec![multisig_a.clone()]);
+        assert_eq!(list(&deps, VOTER2, None, None), vec![multisig_a.clone()]);
+        assert_eq!(list(&deps, OWNER, None, None), vec![]);
+
+        // a second multisig, for VOTER1 only
+        handle(&mut deps, mock_env(OWNER, &[]), create_msg(vec![voter(VOTER1, 1)], "client b")).unwrap();
+        handle(&mut deps, mock_env("multisig_b", &[]), HandleMsg::RegisterMultisig { id: 2 }).unwrap();
+
+        let all = list(&deps, VOTER1, None, None);
+        assert_eq!(all.len(), 2);
+        assert_eq!(list(&deps, VOTER1, None, Some(1)), vec![multisig_a]);
+        assert_eq!(list(&deps, VOTER1, Some("multisig_a"), None), vec![all[1].clone()]);
+        assert_eq!(list(&deps, VOTER2, Some("multisig_a"), None), vec![]);
+
+        let res: ConfigResponse = from_binary(&query(&deps, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(res.multisig_count, 2);
+    }
+}
@@ -0,0 +1,15 @@
+use cosmwasm_std::StdError;
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Label cannot be empty")]
+    EmptyLabel {},
+
+    #[error("No multisig is being instantiated with this id")]
+    UnknownPendingMultisig {},
+}
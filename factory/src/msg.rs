@@ -0,0 +1,59 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::HumanAddr;
+use snip3_fixed_multisig::expiration::Duration;
+use snip3_fixed_multisig::msg::Voter;
+use snip3_fixed_multisig::threshold::Threshold;
+
+use crate::state::MultisigInfo;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InitMsg {
+    /// code id of the uploaded fixed multisig
+    pub multisig_code_id: u64,
+    /// code hash of the uploaded fixed multisig
+    pub multisig_code_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HandleMsg {
+    /// Instantiates a new fixed multisig. Any funds sent are forwarded to it.
+    CreateMultisig {
+        voters: Vec<Voter>,
+        threshold: Threshold,
+        max_voting_period: Duration,
+        label: String,
+    },
+    /// Sent back by a multisig at the end of its `init`, registering its address
+    /// for each of its members
+    RegisterMultisig { id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns ConfigResponse
+    Config {},
+    /// Returns MultisigListResponse with the multisigs `member` is a voter of,
+    /// in creation order
+    ListMultisigs {
+        member: String,
+        start_after: Option<HumanAddr>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ConfigResponse {
+    pub multisig_code_id: u64,
+    pub multisig_code_hash: String,
+    /// number of multisigs created so far
+    pub multisig_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MultisigListResponse {
+    pub multisigs: Vec<MultisigInfo>,
+}
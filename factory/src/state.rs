@@ -0,0 +1,72 @@
+use schemars::JsonSchema;
+use serde::{ Deserialize, Serialize };
+use cosmwasm_std::{ HumanAddr, Storage };
+
+use cosmwasm_storage::{ Singleton, ReadonlySingleton, Bucket, ReadonlyBucket,
+    singleton, singleton_read, bucket, bucket_read };
+
+pub static CONFIG_KEY: &[u8] = b"config";
+pub static MULTISIG_COUNT_KEY: &[u8] = b"multisig_count";
+pub static PENDING_KEY: &[u8] = b"pending";
+pub static REGISTRY_KEY: &[u8] = b"registry";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub multisig_code_id: u64,
+    pub multisig_code_hash: String,
+}
+
+// Init configuration Read/Write functions
+pub fn config<S: Storage>(storage: &mut S) -> Singleton<'_, S, Config> {
+    singleton(storage, CONFIG_KEY)
+}
+
+pub fn config_read<S: Storage>(storage: &S) -> ReadonlySingleton<'_, S, Config> {
+    singleton_read(storage, CONFIG_KEY)
+}
+
+// Multisig Count Read/Write functions
+pub fn multisig_count<S: Storage>(storage: &mut S) -> Singleton<'_, S, u64> {
+    singleton(storage, MULTISIG_COUNT_KEY)
+}
+
+pub fn multisig_count_read<S: Storage>(storage: &S) -> ReadonlySingleton<'_, S, u64> {
+    singleton_read(storage, MULTISIG_COUNT_KEY)
+}
+
+// Pending multisigs Read/Write functions
+// (multisigs being instantiated, until they register back)
+pub fn pending<S: Storage>(storage: &mut S) -> Bucket<'_, S, PendingMultisig> {
+    bucket(PENDING_KEY, storage)
+}
+
+pub fn pending_read<S: Storage>(storage: &S) -> ReadonlyBucket<'_, S, PendingMultisig> {
+    bucket_read(PENDING_KEY, storage)
+}
+
+// Registry Read/Write functions
+// (member address -> multisigs it is a voter of, in creation order)
+pub fn registry<S: Storage>(storage: &mut S) -> Bucket<'_, S, Vec<MultisigInfo>> {
+    bucket(REGISTRY_KEY, storage)
+}
+
+pub fn registry_read<S: Storage>(storage: &S) -> ReadonlyBucket<'_, S, Vec<MultisigInfo>> {
+    bucket_read(REGISTRY_KEY, storage)
+}
+
+/// A multisig instantiated but not registered yet. Instantiation and registration happen
+/// in the same transaction, so a pending entry never outlives it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingMultisig {
+    pub label: String,
+    pub code_hash: String,
+    pub members: Vec<String>,
+}
+
+/// A multisig created by the factory
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MultisigInfo {
+    pub address: HumanAddr,
+    pub code_hash: String,
+    pub label: String,
+}